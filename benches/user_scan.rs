@@ -0,0 +1,135 @@
+//! Benchmark of the `/proc` user scan over a synthetic process table
+//!
+//! Builds a fake procfs with `RUNTIME_BENCH_PROCS` processes (default 40000)
+//! in a temporary directory and compares the previous scanner, which read
+//! `stat` and then `status` for every PID, with `ProcScanner`.
+//!
+//! Run with `cargo bench --bench user_scan`.
+
+use std::collections::HashSet;
+use std::fs;
+use std::os::unix::fs::{chown, symlink, MetadataExt};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use runtime::proc_scan::ProcScanner;
+
+const ROUNDS: usize = 5;
+
+/// One in every `TTY_EVERY` processes has a controlling terminal
+const TTY_EVERY: u32 = 50;
+
+fn build_procfs(root: &Path, count: u32) {
+    for pid in 1..=count {
+        let dir = root.join(pid.to_string());
+        fs::create_dir_all(dir.join("fd")).unwrap();
+
+        let wanted = if pid % 3 == 0 { 1000 + pid % 7 } else { 0 };
+        if wanted != 0 {
+            // Only root can give the directory away; other users keep their own uid
+            let _ = chown(&dir, Some(wanted), None);
+        }
+        // ProcScanner takes the owner of the directory, the legacy scan `status`
+        let uid = fs::metadata(&dir).unwrap().uid();
+        let tty_nr = if pid % TTY_EVERY == 0 { 34816 + (pid % 8) as i32 } else { 0 };
+        // No space in the command name: the legacy scan splits `stat` on whitespace
+        fs::write(dir.join("stat"), format!(
+            "{} (worker) S 1 {} {} {} -1 4194560 100 0 0 0 1 2 0 0 20 0 1 0 100 1000000 200 \
+             18446744073709551615 1 1 0 0 0 0 0 0 0 0 0 0 17 0 0 0 0 0 0 0 0 0 0 0 0 0 0\n",
+            pid, pid, pid, tty_nr)).unwrap();
+        fs::write(dir.join("status"), format!(
+            "Name:\tworker\nUmask:\t0022\nState:\tS (sleeping)\nTgid:\t{pid}\nNgid:\t0\nPid:\t{pid}\n\
+             PPid:\t1\nTracerPid:\t0\nUid:\t{uid}\t{uid}\t{uid}\t{uid}\nGid:\t{uid}\t{uid}\t{uid}\t{uid}\n\
+             FDSize:\t64\nGroups:\t\nVmPeak:\t    1000 kB\nVmSize:\t    1000 kB\nThreads:\t1\n")).unwrap();
+        for fd in 0..8 {
+            symlink("/dev/null", dir.join("fd").join(fd.to_string())).unwrap();
+        }
+    }
+}
+
+/// Removes the synthetic procfs when dropped, also if an assertion fails
+struct TempTree(PathBuf);
+
+impl Drop for TempTree {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// The scanner as it was before `ProcScanner`: split `stat` into a Vec, then
+/// read `status` for the uid
+fn legacy_scan(root: &Path) -> HashSet<u32> {
+    let mut unique_users = HashSet::new();
+
+    for entry in fs::read_dir(root).unwrap().flatten() {
+        if let Some(name) = entry.file_name().to_str() {
+            if name.chars().all(|c| c.is_ascii_digit()) {
+                if let Ok(stat_content) = fs::read_to_string(entry.path().join("stat")) {
+                    let parts: Vec<&str> = stat_content.split_whitespace().collect();
+                    if parts.len() > 6 {
+                        let tty_nr: i32 = parts[6].parse().unwrap_or(0);
+                        if tty_nr > 0 {
+                            if let Ok(status_content) = fs::read_to_string(entry.path().join("status")) {
+                                for line in status_content.lines() {
+                                    if line.starts_with("Uid:") {
+                                        if let Some(uid) = line.split_whitespace().nth(1).and_then(|uid| uid.parse::<u32>().ok()) {
+                                            if uid >= 1000 || uid == 0 {
+                                                unique_users.insert(uid);
+                                            }
+                                        }
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    unique_users
+}
+
+fn scan(scanner: &ProcScanner) -> HashSet<u32> {
+    scanner.terminal_processes()
+        .unwrap()
+        .into_iter()
+        .map(|process| process.uid)
+        .filter(|uid| *uid >= 1000 || *uid == 0)
+        .collect()
+}
+
+/// Best of `ROUNDS` runs
+fn time<T>(mut run: impl FnMut() -> T) -> (Duration, T) {
+    let mut best = Duration::MAX;
+    let mut result = run();
+    for _ in 0..ROUNDS {
+        let started = Instant::now();
+        result = run();
+        best = best.min(started.elapsed());
+    }
+    (best, result)
+}
+
+fn main() {
+    let count = std::env::var("RUNTIME_BENCH_PROCS")
+        .ok()
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(40_000);
+    let tree = TempTree(std::env::temp_dir().join(format!("runtime-bench-procfs-{}", std::process::id())));
+    let root = &tree.0;
+
+    eprintln!("building synthetic procfs with {} processes in {}", count, root.display());
+    build_procfs(root, count);
+
+    let (legacy, expected) = time(|| legacy_scan(root));
+    let (single, single_users) = time(|| scan(&ProcScanner::new(root).threads(1)));
+    let (parallel, parallel_users) = time(|| scan(&ProcScanner::new(root)));
+    assert_eq!(single_users, expected);
+    assert_eq!(parallel_users, expected);
+
+    println!("legacy stat+status scan   {:>10.2?}", legacy);
+    println!("ProcScanner, 1 thread     {:>10.2?}  ({:.1}x)", single, legacy.as_secs_f64() / single.as_secs_f64());
+    println!("ProcScanner, parallel     {:>10.2?}  ({:.1}x)", parallel, legacy.as_secs_f64() / parallel.as_secs_f64());
+}
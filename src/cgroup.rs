@@ -0,0 +1,204 @@
+//! Uptime and resource usage of one service instead of the whole host
+//!
+//! A systemd unit is found by its directory in the cgroup v2 hierarchy
+//! (`/sys/fs/cgroup/system.slice/nginx.service`), so no D-Bus connection is
+//! needed. The unit's uptime is that of its oldest process: `starttime` from
+//! `/proc/<pid>/stat`, counted from `btime` in `/proc/stat`. CPU time, memory
+//! and pressure stall information come from the cgroup's own files.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+
+use crate::proc_scan::{parse_btime, parse_stat_starttime, started_at};
+
+/// Mount point of the unified cgroup hierarchy, relative to the root
+pub const CGROUP_MOUNT: &str = "sys/fs/cgroup";
+
+/// Which cgroup to report on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CgroupTarget {
+    /// A systemd unit; a name without a suffix means `<name>.service`
+    Unit(String),
+    /// A cgroup directory, either under `/sys/fs/cgroup` or relative to it
+    /// as in `/proc/<pid>/cgroup` (`/system.slice/nginx.service`)
+    Path(PathBuf),
+}
+
+impl CgroupTarget {
+    /// Name shown in place of the host: the unit name or the cgroup path
+    pub fn name(&self) -> String {
+        match self {
+            CgroupTarget::Unit(unit) if unit.contains('.') => unit.clone(),
+            CgroupTarget::Unit(unit) => format!("{}.service", unit),
+            CgroupTarget::Path(path) => path.display().to_string(),
+        }
+    }
+
+    /// The cgroup directory below `root` (normally `/`)
+    pub fn resolve(&self, root: &Path) -> io::Result<PathBuf> {
+        let mount = unified_mount(root);
+        match self {
+            CgroupTarget::Unit(_) => {
+                let name = self.name();
+                find_unit(&mount, &name).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, format!("unit {} has no cgroup (is it running?)", name))
+                })
+            }
+            CgroupTarget::Path(path) => {
+                let relative = path.strip_prefix(Path::new("/").join(CGROUP_MOUNT).join("unified"))
+                    .or_else(|_| path.strip_prefix(Path::new("/").join(CGROUP_MOUNT)))
+                    .or_else(|_| path.strip_prefix("/"))
+                    .unwrap_or(path);
+                let dir = mount.join(relative);
+                if dir.join("cgroup.procs").is_file() {
+                    Ok(dir)
+                } else {
+                    Err(io::Error::new(io::ErrorKind::NotFound, format!("{} is not a cgroup v2 directory", path.display())))
+                }
+            }
+        }
+    }
+}
+
+/// Uptime and resource usage of a cgroup
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CgroupStats {
+    /// Unit name or cgroup path, as asked for
+    pub name: String,
+    /// The cgroup directory that was read
+    pub path: PathBuf,
+    /// Oldest process in the cgroup, normally the unit's main PID
+    pub main_pid: Option<u32>,
+    /// When that process started
+    pub started: Option<DateTime<Utc>>,
+    /// Seconds since it started
+    pub uptime_seconds: Option<f64>,
+    /// CPU time used by the whole cgroup, from `cpu.stat`
+    pub cpu_seconds: Option<f64>,
+    /// Memory charged to the cgroup, from `memory.current`
+    pub memory_bytes: Option<u64>,
+    /// Memory limit, from `memory.max`; `None` if unlimited
+    pub memory_max: Option<u64>,
+    /// Share of time tasks waited for CPU, from `cpu.pressure`
+    pub cpu_pressure: Option<Pressure>,
+    /// Share of time tasks waited for memory, from `memory.pressure`
+    pub memory_pressure: Option<Pressure>,
+    /// Share of time tasks waited for I/O, from `io.pressure`
+    pub io_pressure: Option<Pressure>,
+}
+
+impl CgroupStats {
+    /// Read the cgroup and its processes below `root`, measuring uptime at `now`
+    ///
+    /// Fails only if the cgroup cannot be found; files that are missing (for
+    /// example when a controller is not enabled) leave their fields unknown.
+    pub fn read(target: &CgroupTarget, root: &Path, now: DateTime<Utc>) -> io::Result<Self> {
+        let path = target.resolve(root)?;
+        let read = |file: &str| fs::read_to_string(path.join(file)).ok();
+
+        let boot_time = fs::read_to_string(root.join("proc/stat")).ok().as_deref().and_then(parse_btime);
+        let oldest = cgroup_pids(&path)
+            .into_iter()
+            .filter_map(|pid| {
+                let stat = fs::read_to_string(root.join(format!("proc/{}/stat", pid))).ok()?;
+                Some((parse_stat_starttime(&stat)?, pid))
+            })
+            .min();
+        let started = oldest.zip(boot_time).and_then(|((starttime, _), boot_time)| started_at(boot_time, starttime));
+
+        Ok(Self {
+            name: target.name(),
+            main_pid: oldest.map(|(_, pid)| pid),
+            started,
+            uptime_seconds: started.map(|started| ((now - started).num_milliseconds() as f64 / 1000.0).max(0.0)),
+            cpu_seconds: read("cpu.stat").as_deref().and_then(parse_cpu_usage),
+            memory_bytes: read("memory.current").and_then(|value| value.trim().parse().ok()),
+            memory_max: read("memory.max").and_then(|value| value.trim().parse().ok()),
+            cpu_pressure: read("cpu.pressure").as_deref().and_then(Pressure::parse),
+            memory_pressure: read("memory.pressure").as_deref().and_then(Pressure::parse),
+            io_pressure: read("io.pressure").as_deref().and_then(Pressure::parse),
+            path,
+        })
+    }
+}
+
+/// Pressure stall averages of one resource: percent of time at least one
+/// task was stalled over the last 10, 60 and 300 seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pressure {
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+}
+
+impl Pressure {
+    /// The `some` line of a PSI file such as `cpu.pressure`
+    pub fn parse(content: &str) -> Option<Self> {
+        let line = content.lines().find_map(|line| line.strip_prefix("some "))?;
+        let field = |name: &str| {
+            line.split_whitespace()
+                .find_map(|field| field.strip_prefix(name)?.strip_prefix('='))?
+                .parse::<f64>()
+                .ok()
+        };
+        Some(Self { avg10: field("avg10")?, avg60: field("avg60")?, avg300: field("avg300")? })
+    }
+}
+
+/// `usage_usec` of a `cpu.stat` file, in seconds
+pub fn parse_cpu_usage(content: &str) -> Option<f64> {
+    let usec: u64 = content.lines().find_map(|line| line.strip_prefix("usage_usec "))?.trim().parse().ok()?;
+    Some(usec as f64 / 1e6)
+}
+
+/// The cgroup v2 hierarchy below `root`: `/sys/fs/cgroup`, or
+/// `/sys/fs/cgroup/unified` on hosts that still mount the v1 controllers
+fn unified_mount(root: &Path) -> PathBuf {
+    let mount = root.join(CGROUP_MOUNT);
+    let unified = mount.join("unified");
+    if !mount.join("cgroup.controllers").exists() && unified.is_dir() {
+        unified
+    } else {
+        mount
+    }
+}
+
+/// The directory of `unit` in the hierarchy below `mount`, searching breadth-first
+fn find_unit(mount: &Path, unit: &str) -> Option<PathBuf> {
+    let mut queue = vec![mount.to_path_buf()];
+    while !queue.is_empty() {
+        let mut next = Vec::new();
+        for dir in queue {
+            for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+                // file_type does not follow symlinks, so the walk cannot loop
+                if !entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                    continue;
+                }
+                if entry.file_name() == unit {
+                    return Some(entry.path());
+                }
+                next.push(entry.path());
+            }
+        }
+        queue = next;
+    }
+    None
+}
+
+/// Processes in the cgroup and every cgroup below it
+fn cgroup_pids(path: &Path) -> Vec<u32> {
+    let mut pids: Vec<u32> = fs::read_to_string(path.join("cgroup.procs"))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect();
+    for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            pids.extend(cgroup_pids(&entry.path()));
+        }
+    }
+    pids
+}
@@ -0,0 +1,24 @@
+//! Driver for `runtime check`
+//!
+//! Collects only what the checks need, prints the findings in the chosen
+//! format and returns the exit code for the worst verdict.
+
+use std::time::Duration;
+
+use runtime::health::{self, CheckFormat};
+use runtime::system_metrics::MetricSet;
+use runtime::{RuntimeArgs, RuntimeBuilder};
+
+/// Run every check and return the process exit code
+///
+/// The max-uptime policy only runs when a maximum was given.
+pub fn run(args: RuntimeArgs, max_uptime: Option<Duration>, format: CheckFormat) -> i32 {
+    let runtime = RuntimeBuilder::from(args).metric_set(MetricSet::REBOOT | MetricSet::UPTIME).build();
+    let mut findings = vec![health::pending_reboot(runtime.metrics(), runtime.options())];
+    if let Some(max_uptime) = max_uptime {
+        findings.push(health::max_uptime(runtime.metrics(), max_uptime.as_secs_f64(), runtime.options()));
+    }
+
+    println!("{}", health::report(&findings, format));
+    health::exit_code(&findings)
+}
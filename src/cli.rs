@@ -2,8 +2,21 @@
 //!
 //! This module handles argument parsing to exactly match uptime's behavior
 
+use std::path::PathBuf;
+use std::time::Duration;
+
 use clap::{Arg, Command};
-use runtime::{OutputFormat, RuntimeArgs};
+use runtime::{OutputFormat, RuntimeArgs, RuntimeCommand, DEFAULT_SPINNER_DELAY};
+use runtime::cgroup::CgroupTarget;
+use runtime::config::Config;
+use runtime::users::UserFilter;
+use runtime::duration::{parse_duration, DurationSettings, DurationStyle, DurationUnit};
+use runtime::glyphs::Glyphs;
+use runtime::process::ProcessTarget;
+use runtime::health::CheckFormat;
+use runtime::render::{ColorChoice, CountKind, Field, UptimeClock};
+use runtime::theme::Theme;
+use runtime::time_format::{TimeSettings, TimeStyle, TimeZoneChoice};
 
 /// Parse command line arguments exactly like standard uptime
 ///
@@ -15,6 +28,71 @@ pub fn parse_args() -> RuntimeArgs {
         .about("* Modern colorful uptime utility with interactive dashboard *")
         .long_about("A modern replacement for the classic uptime command with beautiful colors,\nanimations, and multiple output formats.")
         .disable_version_flag(true)  // We handle version ourselves
+        .subcommand(
+            Command::new("top")
+                .about("Full-screen dashboard with live load, CPU and memory history")
+                .arg(
+                    Arg::new("interval")
+                        .short('n')
+                        .long("interval")
+                        .value_name("SECONDS")
+                        .help("Refresh interval in seconds")
+                        .default_value("2")
+                        .value_parser(parse_interval),
+                )
+                .arg(
+                    Arg::new("load-threshold")
+                        .long("load-threshold")
+                        .value_name("LOAD")
+                        .help("Load the forecast counts down to [default: number of CPUs]")
+                        .value_parser(parse_load_threshold),
+                ),
+        )
+        .subcommand(
+            Command::new("check")
+                .about("Health checks for scripts: exit 0 if healthy, 4 if a check failed (e.g. reboot pending), 5 if unknown")
+                .arg(
+                    Arg::new("max-uptime")
+                        .long("max-uptime")
+                        .value_name("DURATION")
+                        .help("Fail once the host has been up this long, e.g. 30d, 36h or 2w 3d")
+                        .value_parser(|value: &str| parse_duration(value).map(Duration::from_secs)),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_name("FORMAT")
+                        .help("How to write the findings: text, json or sarif")
+                        .default_value("text")
+                        .value_parser(|value: &str| value.parse::<CheckFormat>()),
+                ),
+        )
+        .subcommand(
+            Command::new("last-boots")
+                .about("Boot history with how each boot ended, from wtmp")
+                .arg(
+                    Arg::new("wtmp")
+                        .long("wtmp")
+                        .value_name("FILE")
+                        .help("wtmp file to read [default: /var/log/wtmp]")
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("journal-boots")
+                        .long("journal-boots")
+                        .value_name("FILE")
+                        .help("Output of `journalctl --utc --list-boots` to take boot IDs and last-seen times from")
+                        .value_parser(clap::value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("limit")
+                        .short('n')
+                        .long("limit")
+                        .value_name("COUNT")
+                        .help("Show only the most recent COUNT boots")
+                        .value_parser(clap::value_parser!(usize)),
+                ),
+        )
         .arg(
             Arg::new("container")
                 .short('c')
@@ -36,6 +114,54 @@ pub fn parse_args() -> RuntimeArgs {
                 .help("Show uptime values in raw machine-readable format")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("json")
+                .short('j')
+                .long("json")
+                .help("Show metrics and host identification as JSON")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("prometheus")
+                .long("prometheus")
+                .help("Show metrics in the Prometheus text format, e.g. for the node exporter's textfile collector")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("csv")
+                .long("csv")
+                .help("Show named fields as comma-separated values")
+                .conflicts_with_all(["unit", "cgroup", "pid", "name"])
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("tsv")
+                .long("tsv")
+                .help("Show named fields as tab-separated values")
+                .conflicts_with_all(["unit", "cgroup", "pid", "name"])
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("env")
+                .long("env")
+                .help("Show RUNTIME_<FIELD>=value lines for `eval \"$(runtime --env)\"`")
+                .conflicts_with_all(["unit", "cgroup", "pid", "name"])
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("fields")
+                .long("fields")
+                .value_name("LIST")
+                .help("Fields for --csv, --tsv and --env (comma-separated), e.g. uptime_seconds,load1 [default: all]")
+                .value_delimiter(',')
+                .value_parser(|s: &str| s.parse::<Field>()),
+        )
+        .arg(
+            Arg::new("no-header")
+                .long("no-header")
+                .help("Leave out the header line of --csv and --tsv")
+                .action(clap::ArgAction::SetTrue),
+        )
         .arg(
             Arg::new("since")
                 .short('s')
@@ -56,6 +182,129 @@ pub fn parse_args() -> RuntimeArgs {
                 .help("Show interactive colorful dashboard (default)")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .help("Never show the loading spinner")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("count")
+                .long("count")
+                .value_name("LIST")
+                .help("Logged-in counts to show: users, sessions, ttys (comma-separated)")
+                .value_delimiter(',')
+                .value_parser(|s: &str| s.parse::<CountKind>()),
+        )
+        .arg(
+            Arg::new("color")
+                .long("color")
+                .value_name("WHEN")
+                .help("When to use colors: auto, always, never")
+                .default_value("auto")
+                .value_parser(parse_color),
+        )
+        .arg(
+            Arg::new("theme")
+                .long("theme")
+                .value_name("NAME|FILE")
+                .help("Dashboard colors: dark, light, high-contrast, colorblind, or a theme file")
+                .value_parser(Theme::load),
+        )
+        .arg(
+            Arg::new("glyphs")
+                .long("glyphs")
+                .value_name("SET")
+                .help("Dashboard borders and icons: ascii, unicode, nerd (ascii on non-UTF-8 locales)")
+                .default_value("ascii")
+                .value_parser(|s: &str| s.parse::<Glyphs>()),
+        )
+        .arg(
+            Arg::new("utc")
+                .long("utc")
+                .help("Print timestamps in UTC")
+                .conflicts_with("tz")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("tz")
+                .long("tz")
+                .value_name("IANA NAME")
+                .help("Print timestamps in the given time zone, e.g. Europe/Berlin")
+                .value_parser(|name: &str| name.parse::<TimeZoneChoice>()),
+        )
+        .arg(
+            Arg::new("iso")
+                .long("iso")
+                .help("Print timestamps in ISO 8601 / RFC 3339 format")
+                .conflicts_with("time-format")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("time-format")
+                .long("time-format")
+                .value_name("STRFTIME")
+                .help("Print every timestamp with this strftime pattern")
+                .value_parser(TimeStyle::custom),
+        )
+        .arg(
+            Arg::new("duration-style")
+                .long("duration-style")
+                .value_name("STYLE")
+                .help("How to write uptimes: compact, long, procps, iso, clock")
+                .value_parser(|s: &str| s.parse::<DurationStyle>()),
+        )
+        .arg(
+            Arg::new("uptime-clock")
+                .long("uptime-clock")
+                .value_name("CLOCK")
+                .help("Uptime to show: boot (includes time suspended) or awake")
+                .value_parser(|s: &str| s.parse::<UptimeClock>()),
+        )
+        .arg(
+            Arg::new("largest-unit")
+                .long("largest-unit")
+                .value_name("UNIT")
+                .help("Largest unit used for uptimes: weeks, days, hours, minutes, seconds")
+                .value_parser(|s: &str| s.parse::<DurationUnit>()),
+        )
+        .arg(
+            Arg::new("smallest-unit")
+                .long("smallest-unit")
+                .value_name("UNIT")
+                .help("Smallest unit used for uptimes: weeks, days, hours, minutes, seconds")
+                .value_parser(|s: &str| s.parse::<DurationUnit>()),
+        )
+        .arg(
+            Arg::new("unit")
+                .long("unit")
+                .value_name("UNIT")
+                .help("Report on a systemd unit instead of the host, e.g. nginx.service")
+                .conflicts_with("cgroup"),
+        )
+        .arg(
+            Arg::new("cgroup")
+                .long("cgroup")
+                .value_name("PATH")
+                .help("Report on a cgroup v2 directory instead of the host, e.g. /sys/fs/cgroup/system.slice/nginx.service")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("pid")
+                .long("pid")
+                .value_name("PID")
+                .help("Report how long a process has been running instead of the host")
+                .conflicts_with_all(["unit", "cgroup", "name"])
+                .value_parser(clap::value_parser!(u32)),
+        )
+        .arg(
+            Arg::new("name")
+                .long("name")
+                .value_name("NAME")
+                .help("Report how long every process with this name has been running")
+                .conflicts_with_all(["unit", "cgroup"]),
+        )
         .arg(
             Arg::new("version")
                 .short('V')
@@ -68,6 +317,16 @@ pub fn parse_args() -> RuntimeArgs {
     // Determine output format based on flags (priority order)
     let format = if matches.get_flag("since") {
         OutputFormat::Since
+    } else if matches.get_flag("json") {
+        OutputFormat::Json
+    } else if matches.get_flag("prometheus") {
+        OutputFormat::Prometheus
+    } else if matches.get_flag("csv") {
+        OutputFormat::Csv
+    } else if matches.get_flag("tsv") {
+        OutputFormat::Tsv
+    } else if matches.get_flag("env") {
+        OutputFormat::KeyValue
     } else if matches.get_flag("raw") {
         OutputFormat::Raw
     } else if matches.get_flag("pretty") {
@@ -81,9 +340,129 @@ pub fn parse_args() -> RuntimeArgs {
         OutputFormat::Interactive
     };
 
+    let zone = if matches.get_flag("utc") {
+        TimeZoneChoice::Utc
+    } else {
+        matches.get_one::<TimeZoneChoice>("tz").cloned().unwrap_or_default()
+    };
+
+    let style = if matches.get_flag("iso") {
+        TimeStyle::Rfc3339
+    } else {
+        matches.get_one::<TimeStyle>("time-format").cloned().unwrap_or_default()
+    };
+
+    let duration = DurationSettings {
+        style: matches.get_one::<DurationStyle>("duration-style").copied(),
+        largest: matches.get_one::<DurationUnit>("largest-unit").copied(),
+        smallest: matches.get_one::<DurationUnit>("smallest-unit").copied(),
+    };
+
+    let config = Config::load().unwrap_or_else(|error| {
+        eprintln!("runtime: ignoring config: {}", error);
+        Config::default()
+    });
+    let quiet = matches.get_flag("quiet") || config_setting(config.bool("quiet")).unwrap_or(false);
+    let spinner_delay = config_setting(config.integer("spinner_delay_ms"))
+        .map_or(DEFAULT_SPINNER_DELAY, |millis| Duration::from_millis(millis.max(0) as u64));
+
+    let uptime_clock = match matches.get_one::<UptimeClock>("uptime-clock") {
+        Some(uptime_clock) => *uptime_clock,
+        None => config_setting(config.string("uptime_clock").and_then(|value| value.map(str::parse).transpose()))
+            .unwrap_or_default(),
+    };
+
+    let user_filter = UserFilter::load(&config).unwrap_or_else(|error| {
+        eprintln!("runtime: ignoring config: {}", error);
+        UserFilter::load(&Config::default()).unwrap_or_default()
+    });
+
+    let cgroup = match (matches.get_one::<String>("unit"), matches.get_one::<PathBuf>("cgroup")) {
+        (Some(unit), _) => Some(CgroupTarget::Unit(unit.clone())),
+        (None, Some(path)) => Some(CgroupTarget::Path(path.clone())),
+        (None, None) => None,
+    };
+
+    let processes = match (matches.get_one::<u32>("pid"), matches.get_one::<String>("name")) {
+        (Some(pid), _) => Some(ProcessTarget::Pid(*pid)),
+        (None, Some(name)) => Some(ProcessTarget::Name(name.clone())),
+        (None, None) => None,
+    };
+
+    let command = match matches.subcommand() {
+        Some(("top", top)) => RuntimeCommand::Top {
+            interval: *top.get_one::<Duration>("interval").expect("interval has a default"),
+            load_threshold: top.get_one::<f64>("load-threshold").copied(),
+        },
+        Some(("last-boots", last_boots)) => RuntimeCommand::LastBoots {
+            wtmp: last_boots.get_one::<PathBuf>("wtmp").cloned(),
+            journal: last_boots.get_one::<PathBuf>("journal-boots").cloned(),
+            limit: last_boots.get_one::<usize>("limit").copied(),
+        },
+        Some(("check", check)) => RuntimeCommand::Check {
+            max_uptime: check.get_one::<Duration>("max-uptime").copied().or_else(|| {
+                config_setting(config.string("check.max_uptime").and_then(|value| value.map(parse_duration).transpose()))
+                    .map(Duration::from_secs)
+            }),
+            format: *check.get_one::<CheckFormat>("format").expect("format has a default"),
+        },
+        _ => RuntimeCommand::Show,
+    };
+
     RuntimeArgs {
+        command,
         format,
         show_container: matches.get_flag("container"),
         show_version: matches.get_flag("version"),
+        quiet,
+        spinner_delay,
+        color: *matches.get_one::<ColorChoice>("color").expect("color has a default"),
+        theme: matches.get_one::<Theme>("theme").cloned().unwrap_or_default(),
+        user_filter,
+        counts: matches.get_many::<CountKind>("count").map(|counts| counts.copied().collect()).unwrap_or_default(),
+        fields: matches.get_many::<Field>("fields").map(|fields| fields.copied().collect()).unwrap_or_default(),
+        omit_header: matches.get_flag("no-header"),
+        glyphs: matches.get_one::<Glyphs>("glyphs").expect("glyphs has a default").supported(),
+        time: TimeSettings { zone, style },
+        duration,
+        uptime_clock,
+        cgroup,
+        processes,
+        width: runtime::table::terminal_width(),
     }
 }
+
+/// Parse a refresh interval given in (possibly fractional) seconds
+fn parse_interval(value: &str) -> Result<Duration, String> {
+    value.parse::<f64>()
+        .ok()
+        .filter(|seconds| seconds.is_finite() && *seconds > 0.0)
+        .map(Duration::from_secs_f64)
+        .ok_or_else(|| format!("invalid interval '{}'", value))
+}
+
+/// Parse a positive load for the forecast threshold
+fn parse_load_threshold(value: &str) -> Result<f64, String> {
+    value.parse::<f64>()
+        .ok()
+        .filter(|load| load.is_finite() && *load > 0.0)
+        .ok_or_else(|| format!("invalid load threshold '{}'", value))
+}
+
+/// Parse the `--color` policy
+fn parse_color(value: &str) -> Result<ColorChoice, String> {
+    match value {
+        "auto" => Ok(ColorChoice::Auto),
+        "always" => Ok(ColorChoice::Always),
+        "never" => Ok(ColorChoice::Never),
+        _ => Err(format!("invalid color choice '{}'", value)),
+    }
+}
+
+/// A config value, warning about and ignoring values of the wrong type
+fn config_setting<T>(value: Result<Option<T>, String>) -> Option<T> {
+    value.unwrap_or_else(|error| {
+        eprintln!("runtime: ignoring config: {}", error);
+        None
+    })
+}
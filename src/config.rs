@@ -0,0 +1,181 @@
+//! User configuration file
+//!
+//! Settings are read from `$XDG_CONFIG_HOME/runtime/config.toml` (or
+//! `~/.config/runtime/config.toml`). Only the TOML subset the settings need is
+//! understood: `key = value` lines with strings, integers, booleans or
+//! single-line arrays, `[section]` headers and `#` comments. Keys inside a
+//! section are looked up as `section.key`.
+//!
+//! ```text
+//! quiet = true
+//! spinner_delay_ms = 300
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A value from the configuration file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigValue {
+    Bool(bool),
+    Integer(i64),
+    String(String),
+    Array(Vec<ConfigValue>),
+}
+
+/// Parsed configuration file
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Config {
+    values: BTreeMap<String, ConfigValue>,
+}
+
+/// `$XDG_CONFIG_HOME/runtime` or `~/.config/runtime`
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|config| config.join("runtime"))
+}
+
+impl Config {
+    /// Load the user's configuration; a missing file gives an empty config
+    pub fn load() -> Result<Self, String> {
+        match config_dir() {
+            Some(dir) => Self::from_file(&dir.join("config.toml")),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Load a configuration file; a missing file gives an empty config
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("cannot read {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Parse configuration file contents
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut config = Self::default();
+        let mut section = String::new();
+
+        for (number, line) in content.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+                section = name.trim().to_string();
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `key = value`", number + 1))?;
+            let key = match key.trim() {
+                "" => return Err(format!("line {}: missing key", number + 1)),
+                key if section.is_empty() => key.to_string(),
+                key => format!("{}.{}", section, key),
+            };
+            let value = parse_value(value.trim()).map_err(|e| format!("line {}: {}", number + 1, e))?;
+
+            config.values.insert(key, value);
+        }
+
+        Ok(config)
+    }
+
+    /// Raw value of a key
+    pub fn get(&self, key: &str) -> Option<&ConfigValue> {
+        self.values.get(key)
+    }
+
+    /// A boolean setting; an error if the key holds another type
+    pub fn bool(&self, key: &str) -> Result<Option<bool>, String> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(ConfigValue::Bool(value)) => Ok(Some(*value)),
+            Some(_) => Err(format!("`{}` must be true or false", key)),
+        }
+    }
+
+    /// An integer setting; an error if the key holds another type
+    pub fn integer(&self, key: &str) -> Result<Option<i64>, String> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(ConfigValue::Integer(value)) => Ok(Some(*value)),
+            Some(_) => Err(format!("`{}` must be an integer", key)),
+        }
+    }
+
+    /// A string setting; an error if the key holds another type
+    pub fn string(&self, key: &str) -> Result<Option<&str>, String> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(ConfigValue::String(value)) => Ok(Some(value)),
+            Some(_) => Err(format!("`{}` must be a string", key)),
+        }
+    }
+}
+
+/// Drop a `#` comment that is not inside a string
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(value: &str) -> Result<ConfigValue, String> {
+    if let Some(inner) = value.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+        return split_array(inner)
+            .into_iter()
+            .filter(|item| !item.is_empty())
+            .map(parse_value)
+            .collect::<Result<_, _>>()
+            .map(ConfigValue::Array);
+    }
+    if let Some(inner) = value.strip_prefix('"').and_then(|rest| rest.strip_suffix('"')) {
+        return Ok(ConfigValue::String(inner.to_string()));
+    }
+
+    match value {
+        "true" => Ok(ConfigValue::Bool(true)),
+        "false" => Ok(ConfigValue::Bool(false)),
+        _ => value
+            .replace('_', "")
+            .parse()
+            .map(ConfigValue::Integer)
+            .map_err(|_| format!("invalid value '{}'", value)),
+    }
+}
+
+/// Split array items on commas outside strings
+fn split_array(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut in_string = false;
+    let mut start = 0;
+
+    for (index, c) in inner.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            ',' if !in_string => {
+                items.push(inner[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(inner[start..].trim());
+    items
+}
@@ -0,0 +1,360 @@
+//! Human duration formatting shared by every output format
+//!
+//! Uptimes are split into units between a configurable largest and smallest
+//! unit, then written in one of several styles:
+//!
+//! | Style     | Example                        |
+//! |-----------|--------------------------------|
+//! | Compact   | `3d 4h 12m 5s`                 |
+//! | Long      | `3 weeks, 2 days, 4 hours`     |
+//! | Procps    | `3 days,  4:12`                |
+//! | Iso8601   | `P3DT4H12M5S`                  |
+//! | Clock     | `76:12:05`                     |
+
+use std::str::FromStr;
+
+/// Units a duration can be split into, from largest to smallest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DurationUnit {
+    Weeks,
+    Days,
+    Hours,
+    Minutes,
+    Seconds,
+}
+
+impl DurationUnit {
+    /// All units, largest first
+    pub const ALL: [DurationUnit; 5] = [
+        DurationUnit::Weeks,
+        DurationUnit::Days,
+        DurationUnit::Hours,
+        DurationUnit::Minutes,
+        DurationUnit::Seconds,
+    ];
+
+    /// Length of one unit in seconds
+    pub fn seconds(&self) -> u64 {
+        match self {
+            DurationUnit::Weeks => 7 * 86400,
+            DurationUnit::Days => 86400,
+            DurationUnit::Hours => 3600,
+            DurationUnit::Minutes => 60,
+            DurationUnit::Seconds => 1,
+        }
+    }
+
+    /// Single-letter suffix used by the compact style
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            DurationUnit::Weeks => "w",
+            DurationUnit::Days => "d",
+            DurationUnit::Hours => "h",
+            DurationUnit::Minutes => "m",
+            DurationUnit::Seconds => "s",
+        }
+    }
+
+    /// Singular English name used by the long style
+    pub fn name(&self) -> &'static str {
+        match self {
+            DurationUnit::Weeks => "week",
+            DurationUnit::Days => "day",
+            DurationUnit::Hours => "hour",
+            DurationUnit::Minutes => "minute",
+            DurationUnit::Seconds => "second",
+        }
+    }
+}
+
+impl FromStr for DurationUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "w" | "week" | "weeks" => Ok(DurationUnit::Weeks),
+            "d" | "day" | "days" => Ok(DurationUnit::Days),
+            "h" | "hour" | "hours" => Ok(DurationUnit::Hours),
+            "m" | "min" | "minute" | "minutes" => Ok(DurationUnit::Minutes),
+            "s" | "sec" | "second" | "seconds" => Ok(DurationUnit::Seconds),
+            _ => Err(format!("unknown duration unit '{}'", s)),
+        }
+    }
+}
+
+/// Parse a duration such as `30d`, `36h` or `2w 3d` into seconds
+///
+/// Every number needs a unit; units may be written as in [`DurationUnit`]'s
+/// `FromStr`, with or without a space after the number.
+pub fn parse_duration(text: &str) -> Result<u64, String> {
+    let error = || format!("invalid duration '{}' (expected e.g. 30d, 36h or 2w 3d)", text);
+    let mut rest = text.trim();
+    let mut total: u64 = 0;
+
+    if rest.is_empty() {
+        return Err(error());
+    }
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let count: u64 = rest[..digits].parse().map_err(|_| error())?;
+        rest = rest[digits..].trim_start();
+
+        let letters = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        let unit: DurationUnit = rest[..letters].parse().map_err(|_| error())?;
+        rest = rest[letters..].trim_start_matches([' ', ',']);
+
+        total = count.checked_mul(unit.seconds()).and_then(|seconds| total.checked_add(seconds)).ok_or_else(error)?;
+    }
+
+    Ok(total)
+}
+
+/// How a duration is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationStyle {
+    /// `3d 4h 12m 5s`
+    Compact,
+    /// `3 weeks, 2 days, 4 hours`
+    Long,
+    /// Classic `uptime` layout: `3 days,  4:12` or `12 min` (fixed units)
+    Procps,
+    /// ISO 8601 duration: `P3DT4H12M5S`
+    Iso8601,
+    /// Clock layout: `76:12:05`
+    Clock,
+}
+
+impl DurationStyle {
+    /// Default (largest, smallest) units for this style
+    pub fn default_units(&self) -> (DurationUnit, DurationUnit) {
+        match self {
+            DurationStyle::Compact => (DurationUnit::Days, DurationUnit::Seconds),
+            DurationStyle::Long => (DurationUnit::Weeks, DurationUnit::Minutes),
+            DurationStyle::Procps => (DurationUnit::Days, DurationUnit::Minutes),
+            DurationStyle::Iso8601 => (DurationUnit::Days, DurationUnit::Seconds),
+            DurationStyle::Clock => (DurationUnit::Hours, DurationUnit::Seconds),
+        }
+    }
+}
+
+impl FromStr for DurationStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "compact" => Ok(DurationStyle::Compact),
+            "long" => Ok(DurationStyle::Long),
+            "procps" => Ok(DurationStyle::Procps),
+            "iso" | "iso8601" => Ok(DurationStyle::Iso8601),
+            "clock" => Ok(DurationStyle::Clock),
+            _ => Err(format!("unknown duration style '{}'", s)),
+        }
+    }
+}
+
+/// A duration style together with the range of units it may use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationFormat {
+    pub style: DurationStyle,
+    pub largest: DurationUnit,
+    pub smallest: DurationUnit,
+}
+
+impl DurationFormat {
+    /// A format using the style's default units
+    pub fn new(style: DurationStyle) -> Self {
+        let (largest, smallest) = style.default_units();
+        Self { style, largest, smallest }
+    }
+
+    /// Largest unit; anything bigger is expressed in this unit
+    pub fn largest(mut self, unit: DurationUnit) -> Self {
+        self.largest = unit;
+        self.smallest = self.smallest.max(unit);
+        self
+    }
+
+    /// Smallest unit; anything smaller is dropped
+    pub fn smallest(mut self, unit: DurationUnit) -> Self {
+        self.smallest = unit;
+        self.largest = self.largest.min(unit);
+        self
+    }
+
+    /// Split a duration into every unit between largest and smallest
+    ///
+    /// Zero-valued units are included so callers can decide what to skip.
+    pub fn split(&self, seconds: u64) -> Vec<(u64, DurationUnit)> {
+        let mut remaining = seconds;
+        DurationUnit::ALL
+            .iter()
+            .filter(|unit| **unit >= self.largest && **unit <= self.smallest)
+            .map(|unit| {
+                let value = remaining / unit.seconds();
+                remaining %= unit.seconds();
+                (value, *unit)
+            })
+            .collect()
+    }
+
+    /// Non-zero units of a duration, largest first
+    ///
+    /// A duration below the smallest unit yields a single zero part.
+    pub fn parts(&self, seconds: u64) -> Vec<(u64, DurationUnit)> {
+        let parts: Vec<_> = self.split(seconds)
+            .into_iter()
+            .filter(|(value, _)| *value > 0)
+            .collect();
+
+        if parts.is_empty() {
+            vec![(0, self.smallest)]
+        } else {
+            parts
+        }
+    }
+
+    /// Format a duration given in seconds
+    pub fn format(&self, seconds: f64) -> String {
+        let seconds = seconds.max(0.0) as u64;
+
+        match self.style {
+            DurationStyle::Compact => self.parts(seconds)
+                .iter()
+                .map(|(value, unit)| format!("{}{}", value, unit.suffix()))
+                .collect::<Vec<_>>()
+                .join(" "),
+            DurationStyle::Long => self.format_long(seconds),
+            DurationStyle::Procps => self.format_procps(seconds),
+            DurationStyle::Iso8601 => self.format_iso8601(seconds),
+            DurationStyle::Clock => self.format_clock(seconds),
+        }
+    }
+
+    fn format_long(&self, seconds: u64) -> String {
+        let parts = self.parts(seconds);
+
+        if parts == [(0, self.smallest)] && self.smallest != DurationUnit::Seconds {
+            return format!("less than a {}", self.smallest.name());
+        }
+
+        parts.iter()
+            .map(|(value, unit)| format!("{} {}{}", value, unit.name(), if *value != 1 { "s" } else { "" }))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Classic procps `uptime` layout, e.g. `3 days,  4:12` or `12 min`
+    ///
+    /// Weeks and days are written out; hours and smaller units become a clock
+    /// such as ` 4:12` (or ` 4:12:05` down to seconds) once there is an hour,
+    /// and `12 min` or `12 min, 5 sec` before that.
+    fn format_procps(&self, seconds: u64) -> String {
+        let split = self.split(seconds);
+        let mut fields: Vec<String> = split.iter()
+            .filter(|(value, unit)| *value > 0 && *unit <= DurationUnit::Days)
+            .map(|(value, unit)| format!("{} {}{}", value, unit.name(), if *value != 1 { "s" } else { "" }))
+            .collect();
+        let clock: Vec<(u64, DurationUnit)> = split.into_iter().filter(|(_, unit)| *unit >= DurationUnit::Hours).collect();
+
+        let time = match clock.as_slice() {
+            [] => None,
+            [(hours, DurationUnit::Hours), (minutes, _), rest @ ..] if *hours > 0 => {
+                let seconds = rest.iter().map(|(value, _)| format!(":{:02}", value)).collect::<String>();
+                Some(format!("{:2}:{:02}{}", hours, minutes, seconds))
+            }
+            clock => {
+                let word = |(value, unit): &(u64, DurationUnit)| match unit {
+                    DurationUnit::Hours => format!("{} hour{}", value, if *value != 1 { "s" } else { "" }),
+                    DurationUnit::Minutes => format!("{} min", value),
+                    _ => format!("{} sec", value),
+                };
+                let words: Vec<String> = clock.iter().filter(|(value, _)| *value > 0).map(word).collect();
+                // Like procps, `2 days, 0 min` rather than dropping the time
+                Some(if words.is_empty() { word(&(0, self.smallest)) } else { words.join(", ") })
+            }
+        };
+
+        match time {
+            Some(time) => fields.push(time),
+            None if fields.is_empty() => fields.push(format!("0 {}s", self.smallest.name())),
+            None => {}
+        }
+        fields.join(", ")
+    }
+
+    /// ISO 8601 allows weeks only on their own (`P2W`), so alongside days or
+    /// a time they are folded into days: `P40DT1H`
+    fn format_iso8601(&self, seconds: u64) -> String {
+        let mut date = String::from("P");
+        let mut time = String::new();
+        let (mut weeks, mut days) = (0, 0);
+
+        for (value, unit) in self.parts(seconds) {
+            match unit {
+                DurationUnit::Weeks => weeks = value,
+                DurationUnit::Days => days = value,
+                _ if value == 0 => {}
+                _ => time.push_str(&format!("{}{}", value, unit.suffix().to_uppercase())),
+            }
+        }
+
+        if weeks > 0 && days == 0 && time.is_empty() {
+            return format!("P{}W", weeks);
+        }
+        days += weeks * 7;
+        if days > 0 {
+            date.push_str(&format!("{}D", days));
+        }
+        if date.len() == 1 && time.is_empty() {
+            return format!("PT0{}", self.smallest.suffix().to_uppercase());
+        }
+        if !time.is_empty() {
+            date.push('T');
+            date.push_str(&time);
+        }
+        date
+    }
+
+    fn format_clock(&self, seconds: u64) -> String {
+        let parts = self.split(seconds);
+        let mut fields = Vec::new();
+
+        for (index, (value, _)) in parts.iter().enumerate() {
+            if index == 0 {
+                fields.push(value.to_string());
+            } else {
+                fields.push(format!("{:02}", value));
+            }
+        }
+
+        fields.join(":")
+    }
+}
+
+impl Default for DurationFormat {
+    fn default() -> Self {
+        Self::new(DurationStyle::Compact)
+    }
+}
+
+/// User overrides applied on top of each format's default duration style
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DurationSettings {
+    pub style: Option<DurationStyle>,
+    pub largest: Option<DurationUnit>,
+    pub smallest: Option<DurationUnit>,
+}
+
+impl DurationSettings {
+    /// The format to use for an output whose own default is `default_style`
+    pub fn resolve(&self, default_style: DurationStyle) -> DurationFormat {
+        let mut format = DurationFormat::new(self.style.unwrap_or(default_style));
+        if let Some(largest) = self.largest {
+            format = format.largest(largest);
+        }
+        if let Some(smallest) = self.smallest {
+            format = format.smallest(smallest);
+        }
+        format
+    }
+}
@@ -0,0 +1,178 @@
+//! Border and icon characters for the Interactive dashboard
+//!
+//! ASCII works everywhere. Unicode adds box-drawing borders and simple
+//! symbols, and Nerd uses icons from the Nerd Fonts private-use range.
+
+use std::str::FromStr;
+
+/// Which characters the dashboard may draw with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Glyphs {
+    /// `+`, `=` and `|`, no icons
+    #[default]
+    Ascii,
+    /// Box-drawing borders and plain Unicode symbols
+    Unicode,
+    /// Rounded borders and Nerd Font icons
+    Nerd,
+}
+
+/// Characters used to draw a table frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Border {
+    pub horizontal: char,
+    pub vertical: char,
+    pub top_left: char,
+    pub top_right: char,
+    pub left_tee: char,
+    pub right_tee: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+}
+
+/// Metrics that can carry an icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Icon {
+    Host,
+    Os,
+    Kernel,
+    Virtualization,
+    Unit,
+    Cpu,
+    Memory,
+    Process,
+    Time,
+    Uptime,
+    Suspend,
+    Boot,
+    History,
+    Reboot,
+    Users,
+    Load,
+    Mode,
+}
+
+impl Glyphs {
+    /// Characters for table borders
+    pub fn border(&self) -> Border {
+        match self {
+            Glyphs::Ascii => Border {
+                horizontal: '=',
+                vertical: '|',
+                top_left: '+',
+                top_right: '+',
+                left_tee: '+',
+                right_tee: '+',
+                bottom_left: '+',
+                bottom_right: '+',
+            },
+            Glyphs::Unicode => Border {
+                horizontal: '─',
+                vertical: '│',
+                top_left: '┌',
+                top_right: '┐',
+                left_tee: '├',
+                right_tee: '┤',
+                bottom_left: '└',
+                bottom_right: '┘',
+            },
+            Glyphs::Nerd => Border {
+                horizontal: '─',
+                vertical: '│',
+                top_left: '╭',
+                top_right: '╮',
+                left_tee: '├',
+                right_tee: '┤',
+                bottom_left: '╰',
+                bottom_right: '╯',
+            },
+        }
+    }
+
+    /// Icon shown before a metric's label, if this glyph set has one
+    pub fn icon(&self, icon: Icon) -> Option<char> {
+        match self {
+            Glyphs::Ascii => None,
+            Glyphs::Unicode => Some(match icon {
+                Icon::Host => '⌂',
+                Icon::Os => '◆',
+                Icon::Kernel => '⚙',
+                Icon::Virtualization => '◫',
+                Icon::Unit => '⧉',
+                Icon::Cpu => '▦',
+                Icon::Memory => '▤',
+                Icon::Process => '▸',
+                Icon::Time => '◷',
+                Icon::Uptime => '↑',
+                Icon::Suspend => '☾',
+                Icon::Boot => '⏻',
+                Icon::History => '↺',
+                Icon::Reboot => '⟳',
+                Icon::Users => '☺',
+                Icon::Load => '≈',
+                Icon::Mode => '▣',
+            }),
+            Glyphs::Nerd => Some(match icon {
+                Icon::Host => '\u{f233}',           // nf-fa-server
+                Icon::Os => '\u{f17c}',             // nf-fa-linux
+                Icon::Kernel => '\u{f013}',         // nf-fa-cog
+                Icon::Virtualization => '\u{f1b3}', // nf-fa-cubes
+                Icon::Unit => '\u{f1b2}',           // nf-fa-cube
+                Icon::Cpu => '\u{f2db}',            // nf-fa-microchip
+                Icon::Memory => '\u{f1c0}',         // nf-fa-database
+                Icon::Process => '\u{f120}',        // nf-fa-terminal
+                Icon::Time => '\u{f017}',           // nf-fa-clock_o
+                Icon::Uptime => '\u{f062}',         // nf-fa-arrow_up
+                Icon::Suspend => '\u{f186}',        // nf-fa-moon_o
+                Icon::Boot => '\u{f011}',           // nf-fa-power_off
+                Icon::History => '\u{f1da}',        // nf-fa-history
+                Icon::Reboot => '\u{f021}',         // nf-fa-refresh
+                Icon::Users => '\u{f0c0}',          // nf-fa-users
+                Icon::Load => '\u{f0e4}',           // nf-fa-tachometer
+                Icon::Mode => '\u{f108}',           // nf-fa-desktop
+            }),
+        }
+    }
+
+    /// `label` prefixed with its icon, or unchanged in ASCII mode
+    pub fn label(&self, icon: Icon, label: &str) -> String {
+        match self.icon(icon) {
+            Some(icon) => format!("{} {}", icon, label),
+            None => label.to_string(),
+        }
+    }
+
+    /// This glyph set if the locale can display it, otherwise ASCII
+    pub fn supported(self) -> Self {
+        if self == Glyphs::Ascii || locale_is_utf8() {
+            self
+        } else {
+            Glyphs::Ascii
+        }
+    }
+}
+
+impl FromStr for Glyphs {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ascii" => Ok(Glyphs::Ascii),
+            "unicode" => Ok(Glyphs::Unicode),
+            "nerd" => Ok(Glyphs::Nerd),
+            _ => Err(format!("unknown glyph set '{}'", s)),
+        }
+    }
+}
+
+/// Whether the character locale (`LC_ALL`, `LC_CTYPE`, then `LANG`) is UTF-8
+pub fn locale_is_utf8() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_some_and(|locale| {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        })
+}
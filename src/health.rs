@@ -0,0 +1,289 @@
+//! Health checks behind `runtime check`
+//!
+//! Each check turns collected metrics into a [`Finding`]; the worst verdict
+//! decides the exit code, so CI jobs and configuration management can gate on
+//! `runtime check` without parsing its output. Findings can also be written
+//! as JSON or as a SARIF 2.1.0 log for code-scanning dashboards.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::render::RenderOptions;
+use crate::system_metrics::SystemMetrics;
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Exit code when every check passed
+pub const EXIT_OK: i32 = 0;
+
+/// Exit code when a check failed, e.g. a reboot is pending
+pub const EXIT_FAILED: i32 = 4;
+
+/// Exit code when a check could not tell, e.g. the metric was not measured
+pub const EXIT_UNKNOWN: i32 = 5;
+
+/// Outcome of one check, ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verdict {
+    Pass,
+    Unknown,
+    Fail,
+}
+
+impl Verdict {
+    /// `OK`, `UNKNOWN` or `FAIL`
+    pub fn label(&self) -> &'static str {
+        match self {
+            Verdict::Pass => "OK",
+            Verdict::Unknown => "UNKNOWN",
+            Verdict::Fail => "FAIL",
+        }
+    }
+
+    /// `pass`, `unknown` or `fail`, for machine-readable reports
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verdict::Pass => "pass",
+            Verdict::Unknown => "unknown",
+            Verdict::Fail => "fail",
+        }
+    }
+
+    /// Process exit code for this verdict
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Verdict::Pass => EXIT_OK,
+            Verdict::Unknown => EXIT_UNKNOWN,
+            Verdict::Fail => EXIT_FAILED,
+        }
+    }
+}
+
+/// Result of one check
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// Stable identifier, e.g. `pending-reboot`
+    pub check: &'static str,
+    pub verdict: Verdict,
+    pub message: String,
+    /// Where the host stands against the maximum uptime, for `max-uptime`
+    pub standing: Option<UptimeStanding>,
+}
+
+/// Uptime measured against a maximum-uptime policy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UptimeStanding {
+    pub uptime_seconds: f64,
+    pub max_uptime_seconds: f64,
+    /// When the host reaches the maximum uptime
+    pub deadline: DateTime<Utc>,
+    /// When to reboot: the deadline, or now if it has passed
+    pub suggested_maintenance: DateTime<Utc>,
+}
+
+impl UptimeStanding {
+    /// Days until the deadline; negative when overdue
+    pub fn days_remaining(&self) -> f64 {
+        (self.max_uptime_seconds - self.uptime_seconds) / SECONDS_PER_DAY
+    }
+
+    /// Whether the maximum uptime has been reached
+    pub fn is_overdue(&self) -> bool {
+        self.uptime_seconds >= self.max_uptime_seconds
+    }
+}
+
+impl Finding {
+    /// `FAIL pending-reboot: reboot pending since ...`
+    pub fn line(&self) -> String {
+        format!("{} {}: {}", self.verdict.label(), self.check, self.message)
+    }
+}
+
+/// Fails if installed updates are waiting for a reboot
+pub fn pending_reboot(metrics: &SystemMetrics, options: &RenderOptions) -> Finding {
+    let (verdict, message) = match metrics.reboot() {
+        None => (Verdict::Unknown, "reboot status was not checked".to_string()),
+        Some(reboot) if reboot.is_pending() => (
+            Verdict::Fail,
+            format!("reboot {}", reboot.describe(|since| options.time.format(since, "%Y-%m-%d %H:%M:%S"))),
+        ),
+        Some(_) => (Verdict::Pass, "no reboot pending".to_string()),
+    };
+
+    Finding { check: "pending-reboot", verdict, message, standing: None }
+}
+
+/// Fails once the host has been up for `max_uptime_seconds` or longer
+///
+/// The deadline counts from boot, including time spent suspended.
+pub fn max_uptime(metrics: &SystemMetrics, max_uptime_seconds: f64, options: &RenderOptions) -> Finding {
+    let Some(uptime_seconds) = metrics.uptime_seconds() else {
+        return Finding {
+            check: "max-uptime",
+            verdict: Verdict::Unknown,
+            message: "uptime was not measured".to_string(),
+            standing: None,
+        };
+    };
+
+    let now = options.clock.now();
+    let remaining = Duration::milliseconds(((max_uptime_seconds - uptime_seconds) * 1000.0) as i64);
+    let deadline = now + remaining;
+    let standing = UptimeStanding {
+        uptime_seconds,
+        max_uptime_seconds,
+        deadline,
+        suggested_maintenance: deadline.max(now),
+    };
+
+    let date = |instant| options.time.format(instant, "%Y-%m-%d");
+    let days = |seconds: f64| format!("{:.1} days", seconds / SECONDS_PER_DAY);
+    let limit = format!("up {}, limit {}", days(uptime_seconds), days(max_uptime_seconds));
+    let (verdict, message) = if standing.is_overdue() {
+        (Verdict::Fail, format!("{}: {} overdue since {}, reboot as soon as possible",
+            limit, days(uptime_seconds - max_uptime_seconds), date(deadline)))
+    } else {
+        (Verdict::Pass, format!("{}: {} remaining, reboot by {}",
+            limit, days(max_uptime_seconds - uptime_seconds), date(deadline)))
+    };
+
+    Finding { check: "max-uptime", verdict, message, standing: Some(standing) }
+}
+
+/// Exit code for a set of findings: the worst verdict wins
+pub fn exit_code(findings: &[Finding]) -> i32 {
+    findings.iter().map(|finding| finding.verdict).max().unwrap_or(Verdict::Pass).exit_code()
+}
+
+/// How `runtime check` writes its findings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CheckFormat {
+    /// One `VERDICT check: message` line per finding
+    #[default]
+    Text,
+    /// A JSON object with the overall verdict and every finding
+    Json,
+    /// A SARIF 2.1.0 log with one result per finding
+    Sarif,
+}
+
+impl FromStr for CheckFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(CheckFormat::Text),
+            "json" => Ok(CheckFormat::Json),
+            "sarif" => Ok(CheckFormat::Sarif),
+            _ => Err(format!("unknown check format '{}' (expected text, json or sarif)", s)),
+        }
+    }
+}
+
+/// Write findings in the given format
+pub fn report(findings: &[Finding], format: CheckFormat) -> String {
+    match format {
+        CheckFormat::Text => findings.iter().map(Finding::line).collect::<Vec<_>>().join("\n"),
+        CheckFormat::Json => pretty(&json_report(findings)),
+        CheckFormat::Sarif => pretty(&sarif_report(findings)),
+    }
+}
+
+fn pretty(value: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(value).expect("JSON values always serialize")
+}
+
+fn timestamp(instant: DateTime<Utc>) -> String {
+    instant.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Check-specific fields of a finding
+fn properties(finding: &Finding) -> serde_json::Map<String, serde_json::Value> {
+    let mut properties = serde_json::Map::new();
+    if let Some(standing) = &finding.standing {
+        let round = |days: f64| (days * 100.0).round() / 100.0;
+        properties.insert("uptime_days".into(), round(standing.uptime_seconds / SECONDS_PER_DAY).into());
+        properties.insert("max_uptime_days".into(), round(standing.max_uptime_seconds / SECONDS_PER_DAY).into());
+        properties.insert("days_remaining".into(), round(standing.days_remaining()).into());
+        properties.insert("deadline".into(), timestamp(standing.deadline).into());
+        properties.insert("suggested_maintenance".into(), timestamp(standing.suggested_maintenance).into());
+    }
+    properties
+}
+
+fn json_report(findings: &[Finding]) -> serde_json::Value {
+    let worst = findings.iter().map(|finding| finding.verdict).max().unwrap_or(Verdict::Pass);
+    let findings: Vec<serde_json::Value> = findings
+        .iter()
+        .map(|finding| {
+            let mut object = serde_json::Map::new();
+            object.insert("check".into(), finding.check.into());
+            object.insert("verdict".into(), finding.verdict.name().into());
+            object.insert("message".into(), finding.message.clone().into());
+            object.extend(properties(finding));
+            serde_json::Value::Object(object)
+        })
+        .collect();
+
+    serde_json::json!({
+        "verdict": worst.name(),
+        "exit_code": worst.exit_code(),
+        "findings": findings,
+    })
+}
+
+/// A minimal SARIF 2.1.0 log: one rule per check, one result per finding
+///
+/// Passing checks are reported with kind `pass`, undecided ones with kind
+/// `review`; only failures carry level `error`.
+fn sarif_report(findings: &[Finding]) -> serde_json::Value {
+    let rules: Vec<serde_json::Value> = findings
+        .iter()
+        .map(|finding| serde_json::json!({
+            "id": finding.check,
+            "shortDescription": { "text": rule_description(finding.check) },
+        }))
+        .collect();
+    let results: Vec<serde_json::Value> = findings
+        .iter()
+        .map(|finding| {
+            let (kind, level) = match finding.verdict {
+                Verdict::Pass => ("pass", "none"),
+                Verdict::Unknown => ("review", "none"),
+                Verdict::Fail => ("fail", "error"),
+            };
+            serde_json::json!({
+                "ruleId": finding.check,
+                "kind": kind,
+                "level": level,
+                "message": { "text": finding.message },
+                "properties": properties(finding),
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "version": "2.1.0",
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "runtime",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn rule_description(check: &str) -> &'static str {
+    match check {
+        "pending-reboot" => "Installed updates are not waiting for a reboot",
+        "max-uptime" => "The host was rebooted within the maximum uptime",
+        _ => "Health check",
+    }
+}
@@ -0,0 +1,220 @@
+//! Which machine the metrics describe
+//!
+//! Hostname and kernel release come from `/proc/sys/kernel`, the OS from
+//! `/etc/os-release`, and the virtualization type from the same hints
+//! `systemd-detect-virt` uses: container marker files first, then the DMI
+//! vendor strings a hypervisor exposes, then the CPU's `hypervisor` flag.
+//! Every path is read below a root directory so tests can use fixtures.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Identification of the host
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HostInfo {
+    pub hostname: Option<String>,
+    /// Kernel release, e.g. `6.1.0-18-amd64`
+    pub kernel_release: Option<String>,
+    pub os: Option<OsRelease>,
+    /// Machine architecture, e.g. `x86_64` or `aarch64`
+    pub architecture: Option<String>,
+    pub virtualization: Option<Virtualization>,
+}
+
+impl HostInfo {
+    /// Read host identification from the filesystem below `root`
+    ///
+    /// Missing files leave their fields unknown. The architecture falls back
+    /// to the one this binary was built for.
+    pub fn read(root: &Path) -> Self {
+        let first_line = |path: &str| {
+            fs::read_to_string(root.join(path))
+                .ok()
+                .and_then(|content| content.lines().next().map(|line| line.trim().to_string()))
+                .filter(|line| !line.is_empty())
+        };
+
+        Self {
+            hostname: first_line("proc/sys/kernel/hostname").or_else(|| first_line("etc/hostname")),
+            kernel_release: first_line("proc/sys/kernel/osrelease"),
+            os: ["etc/os-release", "usr/lib/os-release"]
+                .iter()
+                .find_map(|path| fs::read_to_string(root.join(path)).ok())
+                .and_then(|content| OsRelease::parse(&content)),
+            architecture: first_line("proc/sys/kernel/arch").or_else(|| Some(std::env::consts::ARCH.to_string())),
+            virtualization: Some(detect_virtualization(root)),
+        }
+    }
+
+    /// Read host identification from the running system
+    pub fn system() -> Self {
+        Self::read(Path::new("/"))
+    }
+}
+
+/// The fields of `/etc/os-release` shown to users
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OsRelease {
+    /// `ID`, e.g. `debian`
+    pub id: Option<String>,
+    /// `NAME`, e.g. `Debian GNU/Linux`
+    pub name: String,
+    /// `VERSION`, or `VERSION_ID` if there is no `VERSION`
+    pub version: Option<String>,
+    /// `PRETTY_NAME`, e.g. `Debian GNU/Linux 12 (bookworm)`
+    pub pretty_name: Option<String>,
+}
+
+impl OsRelease {
+    /// Parse os-release(5) contents; `None` if there is neither `NAME` nor `PRETTY_NAME`
+    pub fn parse(content: &str) -> Option<Self> {
+        let fields: BTreeMap<&str, String> = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), unquote(value.trim())))
+            .filter(|(_, value)| !value.is_empty())
+            .collect();
+
+        let pretty_name = fields.get("PRETTY_NAME").cloned();
+        Some(Self {
+            id: fields.get("ID").cloned(),
+            name: fields.get("NAME").cloned().or_else(|| pretty_name.clone())?,
+            version: fields.get("VERSION").or_else(|| fields.get("VERSION_ID")).cloned(),
+            pretty_name,
+        })
+    }
+
+    /// `PRETTY_NAME`, else the name and version
+    pub fn display_name(&self) -> String {
+        match (&self.pretty_name, &self.version) {
+            (Some(pretty_name), _) => pretty_name.clone(),
+            (None, Some(version)) => format!("{} {}", self.name, version),
+            (None, None) => self.name.clone(),
+        }
+    }
+}
+
+/// An os-release value without its shell quoting
+fn unquote(value: &str) -> String {
+    let quoted = value.len() >= 2
+        && (value.starts_with('"') && value.ends_with('"') || value.starts_with('\'') && value.ends_with('\''));
+    if !quoted {
+        return value.to_string();
+    }
+
+    let inner = &value[1..value.len() - 1];
+    if value.starts_with('\'') {
+        return inner.to_string();
+    }
+    let mut unescaped = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// What the host runs on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Virtualization {
+    /// Bare metal, as far as can be told
+    None,
+    /// A virtual machine, with the hypervisor if known (`kvm`, `vmware`, ...)
+    Vm(Option<String>),
+    /// A container, with the runtime if known (`docker`, `podman`, `wsl`, ...)
+    Container(Option<String>),
+}
+
+impl Virtualization {
+    /// `none`, `vm` or `container`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Virtualization::None => "none",
+            Virtualization::Vm(_) => "vm",
+            Virtualization::Container(_) => "container",
+        }
+    }
+
+    /// The hypervisor or container runtime, if known
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Virtualization::None => None,
+            Virtualization::Vm(name) | Virtualization::Container(name) => name.as_deref(),
+        }
+    }
+
+    /// `kvm (vm)`, `container` or `none`
+    pub fn describe(&self) -> String {
+        match self.name() {
+            Some(name) => format!("{} ({})", name, self.kind()),
+            None => self.kind().to_string(),
+        }
+    }
+}
+
+/// DMI vendor and product substrings and the hypervisor they identify
+const DMI_VENDORS: &[(&str, &str)] = &[
+    ("KVM", "kvm"),
+    ("QEMU", "qemu"),
+    ("VMware", "vmware"),
+    ("VirtualBox", "oracle"),
+    ("innotek GmbH", "oracle"),
+    ("Xen", "xen"),
+    ("Amazon EC2", "amazon"),
+    ("Google Compute Engine", "google"),
+    ("Parallels", "parallels"),
+    ("BHYVE", "bhyve"),
+    // Hyper-V; Surface laptops share the vendor but not the product name
+    ("Virtual Machine", "microsoft"),
+];
+
+/// Guess the virtualization type from files below `root`
+pub fn detect_virtualization(root: &Path) -> Virtualization {
+    let read = |path: &str| fs::read_to_string(root.join(path)).ok();
+
+    // Containers first: a container on a VM should say container
+    if let Some(name) = read("run/systemd/container").map(|name| name.trim().to_string()) {
+        return Virtualization::Container((!name.is_empty()).then_some(name));
+    }
+    if root.join("run/.containerenv").exists() {
+        return Virtualization::Container(Some("podman".to_string()));
+    }
+    if root.join(".dockerenv").exists() {
+        return Virtualization::Container(Some("docker".to_string()));
+    }
+    if let Some(environ) = read("proc/1/environ") {
+        if let Some(name) = environ.split('\0').find_map(|variable| variable.strip_prefix("container=")) {
+            return Virtualization::Container(Some(name.to_string()));
+        }
+    }
+    if read("proc/sys/kernel/osrelease").is_some_and(|release| release.to_ascii_lowercase().contains("microsoft")) {
+        return Virtualization::Container(Some("wsl".to_string()));
+    }
+
+    let dmi: String = ["sys_vendor", "product_name", "board_vendor", "bios_vendor"]
+        .iter()
+        .filter_map(|field| read(&format!("sys/class/dmi/id/{}", field)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if let Some((_, name)) = DMI_VENDORS.iter().find(|(marker, _)| dmi.contains(marker)) {
+        return Virtualization::Vm(Some(name.to_string()));
+    }
+    if root.join("proc/xen").exists() {
+        return Virtualization::Vm(Some("xen".to_string()));
+    }
+    if read("proc/cpuinfo").is_some_and(|cpuinfo| {
+        cpuinfo.lines()
+            .filter(|line| line.starts_with("flags"))
+            .any(|line| line.split_whitespace().any(|flag| flag == "hypervisor"))
+    }) {
+        return Virtualization::Vm(None);
+    }
+
+    Virtualization::None
+}
@@ -0,0 +1,34 @@
+//! Driver for `runtime last-boots`
+//!
+//! Reads wtmp, optionally matches boots to a journal export, and prints the
+//! history with `runtime::render::last_boots`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use runtime::render::RenderOptions;
+use runtime::wtmp;
+
+/// Print the boot history, newest first
+pub fn run(options: &RenderOptions, wtmp_path: Option<PathBuf>, journal: Option<PathBuf>, limit: Option<usize>) -> io::Result<()> {
+    let wtmp_path = wtmp_path.unwrap_or_else(|| PathBuf::from(wtmp::DEFAULT_PATH));
+    let records = wtmp::read_records(&wtmp_path)
+        .map_err(|error| io::Error::new(error.kind(), format!("{}: {}", wtmp_path.display(), error)))?;
+    let mut boots = wtmp::boots(&records);
+
+    if let Some(journal) = journal {
+        wtmp::cross_reference(&mut boots, &read_journal(&journal)?);
+    }
+
+    let skip = limit.map_or(0, |limit| boots.len().saturating_sub(limit));
+    println!("{}", runtime::render::last_boots(&boots[skip..], options.clock.now(), options));
+    Ok(())
+}
+
+fn read_journal(path: &Path) -> io::Result<Vec<wtmp::JournalBoot>> {
+    let text = fs::read_to_string(path)
+        .map_err(|error| io::Error::new(error.kind(), format!("{}: {}", path.display(), error)))?;
+    wtmp::parse_list_boots(&text)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), error)))
+}
@@ -1,14 +1,46 @@
 use std::fmt::Display;
-use std::time::{SystemTime, UNIX_EPOCH};
-use colored::*;
-
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub mod cgroup;
+pub mod clock;
+pub mod config;
+pub mod duration;
+pub mod glyphs;
+pub mod health;
+pub mod host_info;
+pub mod load_trend;
+pub mod logind;
+pub mod proc_scan;
+pub mod process;
+pub mod reboot;
+pub mod render;
 pub mod system_metrics;
-use system_metrics::SystemMetrics;
+pub mod table;
+pub mod theme;
+pub mod time_format;
+pub mod tui;
+pub mod users;
+pub mod wtmp;
+use cgroup::CgroupTarget;
+use process::ProcessTarget;
+use clock::{Clock, KernelClock};
+use duration::DurationSettings;
+use glyphs::Glyphs;
+use health::CheckFormat;
+use render::{ColorChoice, CountKind, Field, RenderOptions, UptimeClock};
+use system_metrics::{CollectOptions, MetricSet, MetricsSource, Progress, SystemMetrics};
+use theme::{ColorDepth, Theme};
+use time_format::TimeSettings;
+use users::UserFilter;
 
 /// Runtime structure that holds system metrics and formatting options
 #[derive(Debug, Clone)]
 pub struct Runtime {
-    args: RuntimeArgs,
+    format: OutputFormat,
+    options: RenderOptions,
+    source: MetricsSource,
+    collect: CollectOptions,
     system: SystemMetrics,
 }
 
@@ -21,214 +53,262 @@ impl Default for Runtime {
 impl PartialEq for Runtime {
     fn eq(&self, other: &Self) -> bool {
         self.system == other.system &&
-        self.args.format == other.args.format &&
-        self.args.show_container == other.args.show_container
+        self.format == other.format &&
+        self.options.show_container == other.options.show_container
     }
 }
 
 impl Runtime {
-    /// Creates a new Runtime instance
+    /// Creates a new Runtime instance from parsed command line arguments
+    ///
+    /// Metrics that cannot be read are left unknown rather than failing the
+    /// whole collection.
     pub fn new(args: RuntimeArgs) -> Runtime {
-        Self {
-            args,
-            system: SystemMetrics::new().unwrap_or_default(),
-        }
+        RuntimeBuilder::from(args).build()
     }
 
-    /// Refreshes system metrics
+    /// Start building a Runtime with default settings
+    pub fn builder() -> RuntimeBuilder {
+        RuntimeBuilder::default()
+    }
+
+    /// Refreshes the metrics this Runtime collects
     pub fn refresh(&mut self) {
-        if let Ok(()) = self.system.refresh() {
+        if let Ok(()) = self.source.refresh_with_progress(&mut self.system, &self.collect, &self.options.clock, &mut |_| {}) {
             // Metrics refreshed successfully
         }
     }
 
-    /// Get system uptime as a nicely formatted string with colors
-    fn format_uptime_fancy(&self) -> String {
-        let uptime_secs = self.system.uptime_seconds();
-        let days = uptime_secs as u64 / 86400;
-        let hours = (uptime_secs as u64 % 86400) / 3600;
-        let minutes = (uptime_secs as u64 % 3600) / 60;
-        let seconds = uptime_secs as u64 % 60;
+    /// The most recently collected metrics
+    pub fn metrics(&self) -> &SystemMetrics {
+        &self.system
+    }
 
-        let mut parts = Vec::new();
+    /// The output format used by `Display`
+    pub fn format(&self) -> &OutputFormat {
+        &self.format
+    }
 
-        if days > 0 {
-            parts.push(format!("{}d", days.to_string().bright_cyan().bold()));
-        }
-        if hours > 0 {
-            parts.push(format!("{}h", hours.to_string().bright_green().bold()));
-        }
-        if minutes > 0 {
-            parts.push(format!("{}m", minutes.to_string().bright_yellow().bold()));
-        }
-        if seconds > 0 || parts.is_empty() {
-            parts.push(format!("{}s", seconds.to_string().bright_magenta().bold()));
-        }
+    /// The options passed to the renderers
+    pub fn options(&self) -> &RenderOptions {
+        &self.options
+    }
 
-        parts.join(" ")
+    /// Metrics collected on each refresh
+    pub fn metric_set(&self) -> MetricSet {
+        self.collect.metrics
     }
 
-    /// Get load average with color coding based on system load
-    fn format_load_fancy(&self) -> String {
-        let (load1, load5, load15) = self.system.load_averages();
+    /// Render the current metrics in another output format
+    ///
+    /// Metrics the other format needs but this Runtime does not collect are
+    /// shown as unknown; see [`RuntimeBuilder::metric_set`].
+    pub fn render(&self, format: &OutputFormat) -> String {
+        render::render(format, &self.system, &self.options)
+    }
+}
 
-        let color_load = |load: f64| {
-            if load < 1.0 {
-                format!("{:.2}", load).bright_green().bold()
-            } else if load < 2.0 {
-                format!("{:.2}", load).bright_yellow().bold()
-            } else if load < 4.0 {
-                format!("{:.2}", load).bright_red().bold()
-            } else {
-                format!("{:.2}", load).red().bold()
-            }
-        };
+impl Display for Runtime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(&self.format))
+    }
+}
+
+/// Builder for [`Runtime`] when embedding the crate as a library
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeBuilder {
+    format: OutputFormat,
+    options: RenderOptions,
+    source: MetricsSource,
+    metric_set: Option<MetricSet>,
+    user_filter: UserFilter,
+    kernel_clock: KernelClock,
+    cgroup: Option<CgroupTarget>,
+    processes: Option<ProcessTarget>,
+}
 
-        format!("{}, {}, {}",
-            color_load(load1),
-            color_load(load5),
-            color_load(load15)
-        )
+impl RuntimeBuilder {
+    /// Creates a builder with default settings
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    /// Create a clean table layout without nerd fonts
-    fn create_table(&self) -> String {
-        let border = "=".repeat(55).bright_blue().bold();
-        let uptime_fancy = self.format_uptime_fancy();
-        let load_fancy = self.format_load_fancy();
-        let user_count = self.system.user_count();
+    /// Output format used by `Display`
+    pub fn format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
 
-        let boot_time = self.system.boot_time();
-        let boot_datetime = chrono::DateTime::from_timestamp(boot_time as i64, 0)
-            .unwrap_or_default()
-            .with_timezone(&chrono::Local);
+    /// Color policy for colored formats
+    pub fn color(mut self, color: ColorChoice) -> Self {
+        self.options.color = color;
+        self
+    }
 
-        let current_time = chrono::Local::now();
+    /// Colors for the Interactive dashboard
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.options.theme = theme;
+        self
+    }
 
-        let container_status = if self.args.show_container {
-            "[CONTAINER]".bright_cyan().bold().to_string()
-        } else {
-            "[NATIVE]".bright_green().bold().to_string()
-        };
+    /// Colors the terminal supports; detected from the environment by default
+    pub fn color_depth(mut self, depth: ColorDepth) -> Self {
+        self.options.color_depth = Some(depth);
+        self
+    }
 
-        format!(
-r#"
-+{}+
-| {}  SYSTEM UPTIME DASHBOARD  {} |
-+{}+
-| Current Time    : {}               |
-| System Uptime   : {}                        |
-| Boot Time       : {}        |
-| Active Users    : {} {}                      |
-| Load Average    : {}               |
-| System Mode     : {}               |
-+{}+
-"#,
-            border,
-            "*".bright_yellow(),
-            "*".bright_yellow(),
-            "=".repeat(55).bright_blue().bold(),
-            current_time.format("%H:%M:%S %Z").to_string().bright_white().bold(),
-            uptime_fancy,
-            boot_datetime.format("%Y-%m-%d %H:%M:%S").to_string().bright_white().bold(),
-            user_count.to_string().bright_cyan().bold(),
-            if user_count == 1 { "user" } else { "users" }.dimmed(),
-            load_fancy,
-            container_status,
-            "=".repeat(55).bright_blue().bold()
-        )
+    /// Border and icon characters for the Interactive dashboard
+    pub fn glyphs(mut self, glyphs: Glyphs) -> Self {
+        self.options.glyphs = glyphs;
+        self
     }
-}
 
-impl Display for Runtime {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.args.format {
-            OutputFormat::Raw => {
-                // Format: boot_time uptime_seconds idle_time load1 load5 load15
-                let boot_time = self.system.boot_time();
-                let uptime_secs = self.system.uptime_seconds();
-                let idle_time = self.system.idle_time();
-                let (load1, load5, load15) = self.system.load_averages();
-
-                write!(f, "{} {:.6} {} {:.2} {:.2} {:.2}",
-                    boot_time, uptime_secs, idle_time, load1, load5, load15)
-            }
-            OutputFormat::Pretty => {
-                // Format: "up X hours, Y minutes"
-                let uptime_secs = self.system.uptime_seconds();
-                let hours = uptime_secs / 3600.0;
-                let minutes = (uptime_secs % 3600.0) / 60.0;
-
-                if hours >= 1.0 {
-                    let h = hours as u64;
-                    let m = minutes as u64;
-
-                    if m > 0 {
-                        write!(f, "up {} hour{}, {} minute{}",
-                            h, if h != 1 { "s" } else { "" },
-                            m, if m != 1 { "s" } else { "" })
-                    } else {
-                        write!(f, "up {} hour{}", h, if h != 1 { "s" } else { "" })
-                    }
-                } else {
-                    let m = minutes as u64;
-                    if m > 0 {
-                        write!(f, "up {} minute{}", m, if m != 1 { "s" } else { "" })
-                    } else {
-                        write!(f, "up less than a minute")
-                    }
-                }
-            }
-            OutputFormat::Since => {
-                // Format: "YYYY-MM-DD HH:MM:SS"
-                let boot_time = self.system.boot_time();
-                let datetime = chrono::DateTime::from_timestamp(boot_time as i64, 0)
-                    .unwrap_or_default()
-                    .with_timezone(&chrono::Local);
-                write!(f, "{}", datetime.format("%Y-%m-%d %H:%M:%S"))
-            }
-            OutputFormat::Standard => {
-                // Standard uptime format with time, uptime, users, load averages
-                let now = chrono::Local::now();
-                let time_str = now.format("%H:%M:%S");
-
-                let uptime_secs = self.system.uptime_seconds();
-                let days = uptime_secs as u64 / 86400;
-                let hours = (uptime_secs as u64 % 86400) / 3600;
-                let minutes = (uptime_secs as u64 % 3600) / 60;
-
-                let uptime_str = if days > 0 {
-                    if hours > 0 {
-                        format!("{}:{:02}", days, hours)
-                    } else {
-                        format!("{} day{}", days, if days != 1 { "s" } else { "" })
-                    }
-                } else if hours > 0 {
-                    format!("{}:{:02}", hours, minutes)
-                } else {
-                    format!("{} min", minutes)
-                };
-
-                let user_count = self.system.user_count();
-                let user_str = if user_count == 1 { "user" } else { "users" };
-
-                let (load1, load5, load15) = self.system.load_averages();
-
-                let container_suffix = if self.args.show_container { " (container)" } else { "" };
-
-                write!(f, " {} up {}{}, {} {}, load average: {:.2}, {:.2}, {:.2}",
-                    time_str, uptime_str, container_suffix, user_count, user_str, load1, load5, load15)
-            }
-            OutputFormat::Interactive => {
-                // Clean table format without nerd fonts
-                write!(f, "{}", self.create_table())
-            }
+    /// Clock used for the "current time" fields and to derive boot time
+    pub fn clock(mut self, clock: Clock) -> Self {
+        self.options.clock = clock;
+        self
+    }
+
+    /// Time zone and format for every timestamp
+    pub fn time(mut self, time: TimeSettings) -> Self {
+        self.options.time = time;
+        self
+    }
+
+    /// Overrides for how uptimes are written
+    pub fn duration(mut self, duration: DurationSettings) -> Self {
+        self.options.duration = duration;
+        self
+    }
+
+    /// Whether uptime includes time spent suspended
+    pub fn uptime_clock(mut self, uptime_clock: UptimeClock) -> Self {
+        self.options.uptime_clock = uptime_clock;
+        self
+    }
+
+    /// Clocks used to measure awake and suspended time
+    pub fn kernel_clock(mut self, kernel_clock: KernelClock) -> Self {
+        self.kernel_clock = kernel_clock;
+        self
+    }
+
+    /// Report on a service or cgroup instead of the host; `None` for the host
+    pub fn cgroup(mut self, cgroup: Option<CgroupTarget>) -> Self {
+        self.cgroup = cgroup;
+        self
+    }
+
+    /// Report on processes instead of the host; `None` for the host
+    pub fn processes(mut self, processes: Option<ProcessTarget>) -> Self {
+        self.processes = processes;
+        self
+    }
+
+    /// Terminal width for table layouts; `None` if unknown
+    pub fn width(mut self, width: Option<usize>) -> Self {
+        self.options.width = width;
+        self
+    }
+
+    /// Where metrics are collected from
+    pub fn metrics_source(mut self, source: MetricsSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Metrics to collect; defaults to what the output format displays
+    pub fn metric_set(mut self, set: MetricSet) -> Self {
+        self.metric_set = Some(set);
+        self
+    }
+
+    /// Columns of the Csv, Tsv and KeyValue formats; empty for every field
+    pub fn fields(mut self, fields: Vec<Field>) -> Self {
+        self.options.fields = fields;
+        self
+    }
+
+    /// Leave out the Csv and Tsv header line
+    pub fn omit_header(mut self, omit_header: bool) -> Self {
+        self.options.omit_header = omit_header;
+        self
+    }
+
+    /// Logged-in counts (users, sessions, ttys) the formats show
+    pub fn counts(mut self, counts: Vec<CountKind>) -> Self {
+        self.options.counts = counts;
+        self
+    }
+
+    /// Which accounts count as users
+    pub fn user_filter(mut self, filter: UserFilter) -> Self {
+        self.user_filter = filter;
+        self
+    }
+
+    /// Mark the output as coming from a container
+    pub fn show_container(mut self, show_container: bool) -> Self {
+        self.options.show_container = show_container;
+        self
+    }
+
+    /// Build the Runtime and collect metrics once
+    pub fn build(self) -> Runtime {
+        self.build_with_progress(|_| {})
+    }
+
+    /// Build the Runtime, reporting collection progress to `progress`
+    pub fn build_with_progress(self, mut progress: impl FnMut(Progress)) -> Runtime {
+        let required = match (&self.cgroup, &self.processes) {
+            (Some(_), _) => self.format.required_subject_metrics(MetricSet::CGROUP),
+            (None, Some(_)) => self.format.required_subject_metrics(MetricSet::PROCESSES),
+            (None, None) => self.format.required_metrics(),
+        };
+        let collect = CollectOptions {
+            metrics: self.metric_set.unwrap_or(required),
+            users: self.user_filter,
+            kernel_clock: self.kernel_clock,
+            cgroup: self.cgroup,
+            processes: self.processes,
+            ..CollectOptions::default()
+        };
+        let mut system = SystemMetrics::default();
+        let _ = self.source.refresh_with_progress(&mut system, &collect, &self.options.clock, &mut progress);
+
+        Runtime {
+            format: self.format,
+            options: self.options,
+            source: self.source,
+            collect,
+            system,
         }
     }
 }
 
+impl From<RuntimeArgs> for RuntimeBuilder {
+    fn from(args: RuntimeArgs) -> Self {
+        RuntimeBuilder::new()
+            .format(args.format)
+            .color(args.color)
+            .theme(args.theme)
+            .glyphs(args.glyphs)
+            .user_filter(args.user_filter)
+            .counts(args.counts)
+            .fields(args.fields)
+            .omit_header(args.omit_header)
+            .show_container(args.show_container)
+            .time(args.time)
+            .duration(args.duration)
+            .uptime_clock(args.uptime_clock)
+            .cgroup(args.cgroup)
+            .processes(args.processes)
+            .width(args.width)
+    }
+}
+
 /// Output format options
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub enum OutputFormat {
     /// Standard uptime format
     Standard,
@@ -239,29 +319,145 @@ pub enum OutputFormat {
     /// Show since timestamp
     Since,
     /// Interactive colorful table format
-    Interactive,
+    #[default]
+    Interactive,  // Default to the interactive format
+    /// JSON object with every displayed metric and the host identification
+    Json,
+    /// Prometheus text exposition format
+    Prometheus,
+    /// Named fields as comma-separated values
+    Csv,
+    /// Named fields as tab-separated values
+    Tsv,
+    /// `RUNTIME_<FIELD>=value` lines a shell can `eval`
+    KeyValue,
 }
 
-impl Default for OutputFormat {
-    fn default() -> Self {
-        OutputFormat::Interactive  // Default to the interactive format
+impl OutputFormat {
+    /// Metrics this format displays
+    pub fn required_metrics(&self) -> MetricSet {
+        match self {
+            OutputFormat::Standard => MetricSet::UPTIME | MetricSet::USERS | MetricSet::LOAD,
+            OutputFormat::Pretty => MetricSet::UPTIME,
+            OutputFormat::Raw => MetricSet::UPTIME | MetricSet::BOOT_TIME | MetricSet::LOAD | MetricSet::CPU,
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::KeyValue => {
+                MetricSet::UPTIME | MetricSet::BOOT_TIME | MetricSet::LOAD | MetricSet::CPU | MetricSet::HOST
+            }
+            OutputFormat::Since => MetricSet::BOOT_TIME,
+            OutputFormat::Interactive => {
+                MetricSet::UPTIME | MetricSet::BOOT_TIME | MetricSet::BOOT_HISTORY | MetricSet::USERS | MetricSet::LOAD
+                    | MetricSet::CPU | MetricSet::HOST | MetricSet::REBOOT
+            }
+            OutputFormat::Json => {
+                MetricSet::UPTIME | MetricSet::BOOT_TIME | MetricSet::USERS | MetricSet::LOAD | MetricSet::CPU
+                    | MetricSet::HOST | MetricSet::REBOOT
+            }
+            OutputFormat::Prometheus => {
+                MetricSet::UPTIME | MetricSet::BOOT_TIME | MetricSet::USERS | MetricSet::LOAD | MetricSet::CPU
+                    | MetricSet::HOST | MetricSet::REBOOT
+            }
+        }
+    }
+    /// Metrics this format displays when reporting on a cgroup or processes
+    /// (`subject`) instead of the host
+    ///
+    /// The one-line formats show only the subject; the dashboard, JSON and
+    /// Prometheus keep the host metrics alongside it. The field formats have no
+    /// subject columns and always show the host.
+    pub fn required_subject_metrics(&self, subject: MetricSet) -> MetricSet {
+        match self {
+            OutputFormat::Interactive | OutputFormat::Json | OutputFormat::Prometheus => self.required_metrics() | subject,
+            OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::KeyValue => self.required_metrics(),
+            _ => subject,
+        }
     }
 }
 
+/// Which action the command line asked for
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum RuntimeCommand {
+    /// Print the metrics once in the selected format
+    #[default]
+    Show,
+    /// Full-screen dashboard refreshing every `interval`
+    Top {
+        interval: Duration,
+        /// Load the forecast counts towards; the number of CPUs if `None`
+        load_threshold: Option<f64>,
+    },
+    /// Boot history from wtmp, newest first
+    LastBoots {
+        /// wtmp file to read instead of `/var/log/wtmp`
+        wtmp: Option<PathBuf>,
+        /// `journalctl --utc --list-boots` export to take boot IDs from
+        journal: Option<PathBuf>,
+        /// Show at most this many boots
+        limit: Option<usize>,
+    },
+    /// Run health checks and exit with their worst verdict
+    Check {
+        /// Fail once the host has been up this long
+        max_uptime: Option<Duration>,
+        /// How to write the findings
+        format: CheckFormat,
+    },
+}
+
+/// Collection time after which the loading spinner appears
+pub const DEFAULT_SPINNER_DELAY: Duration = Duration::from_millis(150);
+
 /// Command line arguments structure
 #[derive(Debug, Clone, PartialEq)]
 pub struct RuntimeArgs {
+    pub command: RuntimeCommand,
     pub format: OutputFormat,
     pub show_container: bool,
     pub show_version: bool,
+    /// Never show the loading spinner
+    pub quiet: bool,
+    /// How long collection may take before the loading spinner appears
+    pub spinner_delay: Duration,
+    pub color: ColorChoice,
+    pub theme: Theme,
+    pub glyphs: Glyphs,
+    pub user_filter: UserFilter,
+    pub counts: Vec<CountKind>,
+    /// Columns of the tabular formats; empty for every field
+    pub fields: Vec<Field>,
+    /// Leave out the Csv and Tsv header line
+    pub omit_header: bool,
+    pub time: TimeSettings,
+    pub duration: DurationSettings,
+    pub uptime_clock: UptimeClock,
+    /// Service or cgroup to report on instead of the host
+    pub cgroup: Option<CgroupTarget>,
+    /// Processes to report on instead of the host
+    pub processes: Option<ProcessTarget>,
+    pub width: Option<usize>,
 }
 
 impl Default for RuntimeArgs {
     fn default() -> Self {
         Self {
+            command: RuntimeCommand::Show,
             format: OutputFormat::Interactive,
             show_container: false,
             show_version: false,
+            quiet: false,
+            spinner_delay: DEFAULT_SPINNER_DELAY,
+            color: ColorChoice::Auto,
+            theme: Theme::default(),
+            glyphs: Glyphs::Ascii,
+            user_filter: UserFilter::default(),
+            counts: Vec::new(),
+            fields: Vec::new(),
+            omit_header: false,
+            time: TimeSettings::default(),
+            duration: DurationSettings::default(),
+            uptime_clock: UptimeClock::Boot,
+            cgroup: None,
+            processes: None,
+            width: None,
         }
     }
 }
@@ -0,0 +1,139 @@
+//! Which way the load is heading
+//!
+//! The kernel's three load averages decay over 1, 5 and 15 minutes, so the
+//! 1-minute value running above both longer ones means load is rising. When
+//! samples are collected repeatedly, a straight line fitted to the recent
+//! 1-minute values also estimates when the load will reach a threshold such
+//! as the number of CPUs. Only `runtime top` keeps that history, so the
+//! forecast appears there alone; the dashboard and JSON show the direction.
+
+use crate::glyphs::Glyphs;
+
+/// Smallest load difference that counts as movement
+const MIN_TOLERANCE: f64 = 0.05;
+
+/// Share of the longer averages that counts as movement, so busy hosts need
+/// proportionally larger changes
+const RELATIVE_TOLERANCE: f64 = 0.1;
+
+/// Most recent samples the forecast is fitted to
+pub const FORECAST_WINDOW: usize = 30;
+
+/// Forecasts further out than this are not shown
+const FORECAST_HORIZON_SECONDS: f64 = 86400.0;
+
+/// Direction of the load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Rising,
+    Falling,
+    Steady,
+}
+
+impl Trend {
+    /// `rising`, `falling` or `steady`
+    pub fn label(&self) -> &'static str {
+        match self {
+            Trend::Rising => "rising",
+            Trend::Falling => "falling",
+            Trend::Steady => "steady",
+        }
+    }
+
+    /// Arrow for this direction: `^`, `v` and `-` in ASCII
+    pub fn arrow(&self, glyphs: Glyphs) -> char {
+        match (glyphs, self) {
+            (Glyphs::Ascii, Trend::Rising) => '^',
+            (Glyphs::Ascii, Trend::Falling) => 'v',
+            (Glyphs::Ascii, Trend::Steady) => '-',
+            (Glyphs::Unicode | Glyphs::Nerd, Trend::Rising) => '↗',
+            (Glyphs::Unicode | Glyphs::Nerd, Trend::Falling) => '↘',
+            (Glyphs::Unicode | Glyphs::Nerd, Trend::Steady) => '→',
+        }
+    }
+
+    /// The label with its arrow, e.g. `↗ rising` or `^ rising`
+    pub fn describe(&self, glyphs: Glyphs) -> String {
+        format!("{} {}", self.arrow(glyphs), self.label())
+    }
+}
+
+/// Load direction derived from the three load averages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadTrend {
+    pub trend: Trend,
+    /// 1-minute minus 5-minute load
+    pub short_term: f64,
+    /// 1-minute minus 15-minute load
+    pub long_term: f64,
+}
+
+impl LoadTrend {
+    /// Rising if the 1-minute load is above both longer averages, falling if
+    /// below both; anything else, including mixed signals, is steady
+    pub fn from_averages((load1, load5, load15): (f64, f64, f64)) -> Self {
+        let tolerance = (load5.max(load15) * RELATIVE_TOLERANCE).max(MIN_TOLERANCE);
+        let short_term = load1 - load5;
+        let long_term = load1 - load15;
+
+        let trend = if short_term > tolerance && long_term > tolerance {
+            Trend::Rising
+        } else if short_term < -tolerance && long_term < -tolerance {
+            Trend::Falling
+        } else {
+            Trend::Steady
+        };
+
+        Self { trend, short_term, long_term }
+    }
+}
+
+/// Straight-line forecast of the 1-minute load
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Forecast {
+    /// Change of the 1-minute load per minute
+    pub slope_per_minute: f64,
+    /// Load the ETA counts towards
+    pub threshold: f64,
+    /// Seconds until the threshold is reached: `Some(0.0)` if it already is,
+    /// `None` if the load is not heading there within a day
+    pub eta_seconds: Option<f64>,
+}
+
+impl Forecast {
+    /// Fit `(seconds, load1)` samples, oldest first, using the newest
+    /// [`FORECAST_WINDOW`]
+    ///
+    /// Needs at least three samples spread over time.
+    pub fn fit(samples: &[(f64, f64)], threshold: f64) -> Option<Self> {
+        let samples = &samples[samples.len().saturating_sub(FORECAST_WINDOW)..];
+        if samples.len() < 3 {
+            return None;
+        }
+
+        let count = samples.len() as f64;
+        let mean_time = samples.iter().map(|(time, _)| time).sum::<f64>() / count;
+        let mean_load = samples.iter().map(|(_, load)| load).sum::<f64>() / count;
+        let spread: f64 = samples.iter().map(|(time, _)| (time - mean_time).powi(2)).sum();
+        if spread <= 0.0 {
+            return None;
+        }
+        let slope = samples.iter().map(|(time, load)| (time - mean_time) * (load - mean_load)).sum::<f64>() / spread;
+
+        let (_, current) = samples[samples.len() - 1];
+        let eta_seconds = if current >= threshold {
+            Some(0.0)
+        } else if slope > 0.0 {
+            Some((threshold - current) / slope).filter(|eta| *eta <= FORECAST_HORIZON_SECONDS)
+        } else {
+            None
+        };
+
+        Some(Self { slope_per_minute: slope * 60.0, threshold, eta_seconds })
+    }
+}
+
+/// Default forecast threshold: one runnable task per CPU
+pub fn default_threshold() -> f64 {
+    std::thread::available_parallelism().map_or(1, |cpus| cpus.get()) as f64
+}
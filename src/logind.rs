@@ -0,0 +1,216 @@
+//! Sessions and users tracked by systemd-logind
+//!
+//! logind keeps its state in `/run/systemd/sessions/<id>` and
+//! `/run/systemd/users/<uid>`, one `KEY=value` per line. These files are the
+//! authoritative session list on systemd hosts, so they are preferred over
+//! guessing from `/proc`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+
+/// Where logind keeps its state files
+pub const DEFAULT_ROOT: &str = "/run/systemd";
+
+/// What a session is for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionClass {
+    /// A regular login
+    User,
+    /// A display manager's login screen
+    Greeter,
+    /// A screen locker
+    LockScreen,
+    /// A background session, e.g. cron or `systemd-run --user`
+    Background,
+    /// A class this version does not know about
+    Other(String),
+}
+
+impl SessionClass {
+    fn parse(value: &str) -> Self {
+        match value {
+            "user" => SessionClass::User,
+            "greeter" => SessionClass::Greeter,
+            "lock-screen" => SessionClass::LockScreen,
+            "background" => SessionClass::Background,
+            other => SessionClass::Other(other.to_string()),
+        }
+    }
+}
+
+/// Lifecycle state of a session or user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionState {
+    /// Logged in, in the foreground of its seat
+    Active,
+    /// Logged in, not in the foreground
+    Online,
+    /// Logged out, processes still lingering
+    Closing,
+    /// User with lingering enabled but no sessions
+    Lingering,
+    /// Not logged in
+    Offline,
+    /// A state this version does not know about
+    Other(String),
+}
+
+impl SessionState {
+    fn parse(value: &str) -> Self {
+        match value {
+            "active" => SessionState::Active,
+            "online" => SessionState::Online,
+            "closing" => SessionState::Closing,
+            "lingering" => SessionState::Lingering,
+            "offline" => SessionState::Offline,
+            other => SessionState::Other(other.to_string()),
+        }
+    }
+}
+
+/// One `/run/systemd/sessions/<id>` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogindSession {
+    pub id: String,
+    pub uid: u32,
+    pub user: Option<String>,
+    pub seat: Option<String>,
+    /// Terminal without `/dev/`, e.g. `pts/0` or `tty2`
+    pub tty: Option<String>,
+    pub remote_host: Option<String>,
+    pub class: SessionClass,
+    pub state: SessionState,
+    /// When the session started
+    pub started: Option<DateTime<Utc>>,
+}
+
+impl LogindSession {
+    /// Parse a session file; `None` if it lacks a UID
+    pub fn parse(id: &str, content: &str) -> Option<Self> {
+        let fields = parse_fields(content);
+        let text = |key: &str| fields.get(key).filter(|value| !value.is_empty()).map(|value| value.to_string());
+
+        Some(Self {
+            id: id.to_string(),
+            uid: fields.get("UID")?.parse().ok()?,
+            user: text("USER"),
+            seat: text("SEAT"),
+            tty: text("TTY").map(|tty| tty.trim_start_matches("/dev/").to_string()),
+            remote_host: text("REMOTE_HOST"),
+            class: SessionClass::parse(fields.get("CLASS").copied().unwrap_or("user")),
+            state: SessionState::parse(fields.get("STATE").copied().unwrap_or("")),
+            started: fields.get("REALTIME").and_then(|usec| timestamp(usec)),
+        })
+    }
+
+    /// Whether this is a user login that is still open
+    pub fn is_login(&self) -> bool {
+        self.class == SessionClass::User && self.state != SessionState::Closing
+    }
+}
+
+/// One `/run/systemd/users/<uid>` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogindUser {
+    pub uid: u32,
+    pub name: Option<String>,
+    pub state: SessionState,
+    /// IDs of the user's sessions
+    pub sessions: Vec<String>,
+    /// When the user's first session started
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl LogindUser {
+    /// Parse a user file named after the UID; `None` if the name is not a UID
+    pub fn parse(uid: &str, content: &str) -> Option<Self> {
+        let fields = parse_fields(content);
+
+        Some(Self {
+            uid: uid.parse().ok()?,
+            name: fields.get("NAME").map(|name| name.to_string()),
+            state: SessionState::parse(fields.get("STATE").copied().unwrap_or("")),
+            sessions: fields.get("SESSIONS")
+                .map(|sessions| sessions.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+            since: fields.get("REALTIME").and_then(|usec| timestamp(usec)),
+        })
+    }
+}
+
+/// logind's view of who is logged in
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Logind {
+    pub sessions: Vec<LogindSession>,
+    pub users: Vec<LogindUser>,
+}
+
+impl Logind {
+    /// Read the state files below `root` (normally `/run/systemd`)
+    ///
+    /// Fails if the sessions directory is missing, i.e. logind is not running.
+    /// Unreadable or malformed individual files are skipped.
+    pub fn read(root: &Path) -> io::Result<Self> {
+        let mut sessions: Vec<LogindSession> = read_state_dir(&root.join("sessions"))?
+            .into_iter()
+            .filter_map(|(id, content)| LogindSession::parse(&id, &content))
+            .collect();
+        let mut users: Vec<LogindUser> = read_state_dir(&root.join("users"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(uid, content)| LogindUser::parse(&uid, &content))
+            .collect();
+
+        sessions.sort_by(|a, b| a.id.cmp(&b.id));
+        users.sort_by_key(|user| user.uid);
+        Ok(Self { sessions, users })
+    }
+
+    /// Read logind's state from [`DEFAULT_ROOT`]
+    pub fn system() -> io::Result<Self> {
+        Self::read(Path::new(DEFAULT_ROOT))
+    }
+
+    /// Open user logins, excluding greeters, background and closing sessions
+    pub fn logins(&self) -> impl Iterator<Item = &LogindSession> {
+        self.sessions.iter().filter(|session| session.is_login())
+    }
+}
+
+/// `(file name, contents)` of every regular file in `dir`, skipping `*.ref` FIFOs
+fn read_state_dir(dir: &Path) -> io::Result<Vec<(String, String)>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path: PathBuf = entry.path();
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if name.ends_with(".ref") || !entry.file_type().is_ok_and(|kind| kind.is_file()) {
+            continue;
+        }
+        if let Ok(content) = fs::read_to_string(&path) {
+            files.push((name, content));
+        }
+    }
+
+    Ok(files)
+}
+
+/// `KEY=value` lines, ignoring comments
+fn parse_fields(content: &str) -> BTreeMap<&str, &str> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .collect()
+}
+
+/// A logind `REALTIME` value (microseconds since the epoch)
+fn timestamp(usec: &str) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_micros(usec.parse().ok()?)
+}
@@ -1,56 +1,64 @@
-use runtime::{Runtime, OutputFormat};
-use std::time::Duration;
-use std::thread;
-use colored::*;
+use std::path::Path;
 
+use runtime::system_metrics::MetricSet;
+use runtime::theme::{ColorDepth, Painter, Role};
+use runtime::{OutputFormat, RuntimeBuilder, RuntimeCommand};
+
+mod check;
 mod cli;
+mod last_boots;
+mod spinner;
+mod top;
+
+use spinner::Spinner;
 
 fn main() {
     let args = cli::parse_args();
 
     // Handle version flag
     if args.show_version {
-        println!("{}",
-            format!("* Runtime [Freaky Fork] {} *", env!("CARGO_PKG_VERSION"))
-                .bright_green().bold()
-        );
+        let painter = Painter::new(&args.theme, ColorDepth::detect(), args.color.enabled());
+        println!("{}", painter.paint(Role::Title, format!("* Runtime [Freaky Fork] {} *", env!("CARGO_PKG_VERSION"))));
         return;
     }
 
-    // Show minimal loading animation only for interactive mode
-    if args.format == OutputFormat::Interactive {
-        show_fast_loading();
+    if let RuntimeCommand::Top { interval, load_threshold } = args.command {
+        if let Err(error) = top::run(args, interval, load_threshold) {
+            eprintln!("runtime top: {}", error);
+            std::process::exit(1);
+        }
+        return;
     }
 
-    // Create runtime and collect metrics
-    let mut runtime = Runtime::new(args);
-    runtime.refresh();
-
-    // Print the result
-    println!("{}", runtime);
-}
-
-fn show_fast_loading() {
-    let frames = ["|", "/", "-", "\\"];
-
-    print!("{}", "Loading system metrics".bright_cyan().bold());
+    if let RuntimeCommand::Check { max_uptime, format } = args.command {
+        std::process::exit(check::run(args, max_uptime, format));
+    }
 
-    // Much faster animation - only 200ms total
-    for _ in 0..8 {
-        for frame in &frames {
-            print!("\r{} {}",
-                "Loading system metrics".bright_cyan().bold(),
-                frame.bright_yellow().bold()
-            );
-            std::io::Write::flush(&mut std::io::stdout()).unwrap();
-            thread::sleep(Duration::from_millis(25)); // Much faster!
+    if let RuntimeCommand::LastBoots { wtmp, journal, limit } = args.command.clone() {
+        let runtime = RuntimeBuilder::from(args).metric_set(MetricSet::EMPTY).build();
+        if let Err(error) = last_boots::run(runtime.options(), wtmp, journal, limit) {
+            eprintln!("runtime last-boots: {}", error);
+            std::process::exit(1);
         }
+        return;
     }
 
-    println!("\r{} {}",
-        "Loading system metrics".bright_cyan().bold(),
-        "Done!".bright_green().bold()
-    );
+    // Fail early rather than silently falling back to the host
+    if let Some(Err(error)) = args.cgroup.as_ref().map(|target| target.resolve(Path::new("/"))) {
+        eprintln!("runtime: {}", error);
+        std::process::exit(1);
+    }
+    if let Some(Err(error)) = args.processes.as_ref().map(|target| target.find(Path::new("/"))) {
+        eprintln!("runtime: {}", error);
+        std::process::exit(1);
+    }
+
+    // The spinner only appears if collection is slow; it is never shown for
+    // scripted formats
+    let enabled = !args.quiet && args.format == OutputFormat::Interactive;
+    let spinner = Spinner::start(args.spinner_delay, enabled, args.theme.clone(), args.color);
+    let runtime = RuntimeBuilder::from(args).build_with_progress(|progress| spinner.update(progress));
+    spinner.finish();
 
-    thread::sleep(Duration::from_millis(50)); // Minimal delay
+    println!("{}", runtime);
 }
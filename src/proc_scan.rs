@@ -0,0 +1,222 @@
+//! Fast walk over `/proc/<pid>` to find processes attached to a terminal
+//!
+//! Each process costs one `stat()` of its directory (the owner is the
+//! process's uid) and one read of `/proc/<pid>/stat` into a reused buffer,
+//! parsed in place. Large process tables are split across threads.
+//!
+//! The owner of `/proc/<pid>` is the *effective* uid. Earlier versions read
+//! the real uid from the `Uid:` line of `/proc/<pid>/status`, so a setuid
+//! program now counts as the user it runs as rather than the one who
+//! started it.
+//!
+//! The `stat` parsers are shared with the modules that report process and
+//! service start times.
+
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use chrono::{DateTime, Utc};
+
+/// Process count above which the walk is split across threads
+const PARALLEL_THRESHOLD: usize = 4096;
+
+/// Upper bound on worker threads when parallelism is automatic
+const MAX_THREADS: usize = 8;
+
+/// A process with a controlling terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TtyProcess {
+    pub pid: u32,
+    /// Owner of `/proc/<pid>`, i.e. the process's effective uid
+    pub uid: u32,
+    /// `session` field of `/proc/<pid>/stat`: the session leader's PID
+    pub session: i32,
+    /// `tty_nr` field of `/proc/<pid>/stat`
+    pub tty_nr: i32,
+}
+
+/// Scanner over a procfs mount
+///
+/// Processes are attributed to the owner of their `/proc/<pid>` directory,
+/// which is the effective uid, not the real uid reported by `status`.
+#[derive(Debug, Clone)]
+pub struct ProcScanner {
+    root: PathBuf,
+    threads: Option<usize>,
+}
+
+impl Default for ProcScanner {
+    fn default() -> Self {
+        Self::new("/proc")
+    }
+}
+
+impl ProcScanner {
+    /// Scanner over the procfs mounted at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), threads: None }
+    }
+
+    /// Number of worker threads; by default large process tables use up to
+    /// eight threads and small ones are walked on the calling thread
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads.max(1));
+        self
+    }
+
+    /// PIDs of every process, in directory order
+    pub fn pids(&self) -> io::Result<Vec<u32>> {
+        Ok(fs::read_dir(&self.root)?
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str().and_then(|name| name.parse().ok()))
+            .collect())
+    }
+
+    /// Every process whose `stat` reports a controlling terminal
+    ///
+    /// Processes that exit during the walk are skipped. Fails only if the
+    /// procfs root itself cannot be listed.
+    pub fn terminal_processes(&self) -> io::Result<Vec<TtyProcess>> {
+        let pids = self.pids()?;
+        let threads = self.threads.unwrap_or_else(|| {
+            if pids.len() < PARALLEL_THRESHOLD {
+                1
+            } else {
+                thread::available_parallelism().map_or(1, |n| n.get()).min(MAX_THREADS)
+            }
+        });
+
+        if threads <= 1 {
+            return Ok(self.scan_chunk(&pids));
+        }
+
+        let chunk_size = pids.len().div_ceil(threads).max(1);
+        Ok(thread::scope(|scope| {
+            let workers: Vec<_> = pids
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || self.scan_chunk(chunk)))
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap_or_default())
+                .collect()
+        }))
+    }
+
+    fn scan_chunk(&self, pids: &[u32]) -> Vec<TtyProcess> {
+        let mut buffer = String::with_capacity(512);
+        let mut path = self.root.clone();
+        let mut found = Vec::new();
+
+        for &pid in pids {
+            path.push(pid.to_string());
+            if let Some(process) = read_tty_process(&mut path, pid, &mut buffer) {
+                found.push(process);
+            }
+            path.pop();
+        }
+
+        found
+    }
+
+    /// Terminal a process has open on stdin, stdout or stderr, with its uid
+    ///
+    /// Fallback for systems where `tty_nr` is not reported (some containers).
+    /// Only the three standard descriptors are checked rather than the whole
+    /// `fd` directory.
+    pub fn fd_terminals(&self) -> io::Result<Vec<(u32, String)>> {
+        let mut found = Vec::new();
+
+        for pid in self.pids()? {
+            let process = self.root.join(pid.to_string());
+            let terminal = (0..3).find_map(|fd| {
+                let target = fs::read_link(process.join("fd").join(fd.to_string())).ok()?;
+                let target = target.to_str()?;
+                (target.starts_with("/dev/pts/") || target.starts_with("/dev/tty"))
+                    .then(|| target.trim_start_matches("/dev/").to_string())
+            });
+
+            if let (Some(tty), Ok(metadata)) = (terminal, fs::metadata(&process)) {
+                found.push((metadata.uid(), tty));
+            }
+        }
+
+        Ok(found)
+    }
+}
+
+/// `path` is `/proc/<pid>` on entry and on return
+fn read_tty_process(path: &mut PathBuf, pid: u32, buffer: &mut String) -> Option<TtyProcess> {
+    let uid = fs::metadata(&*path).ok()?.uid();
+
+    path.push("stat");
+    let read = read_into(path, buffer);
+    path.pop();
+    read.ok()?;
+
+    let tty_nr = parse_stat_tty(buffer)?;
+    let session = parse_stat_session(buffer)?;
+    (tty_nr > 0).then_some(TtyProcess { pid, uid, session, tty_nr })
+}
+
+fn read_into(path: &Path, buffer: &mut String) -> io::Result<()> {
+    buffer.clear();
+    File::open(path)?.read_to_string(buffer)?;
+    Ok(())
+}
+
+/// `tty_nr` (field 7) of a `/proc/<pid>/stat` line
+///
+/// The command name (field 2) may contain spaces and parentheses, so fields
+/// are counted from the last `)`.
+pub fn parse_stat_tty(stat: &str) -> Option<i32> {
+    stat_field(stat, 7)?.parse().ok()
+}
+
+/// `session` (field 6) of a `/proc/<pid>/stat` line
+pub fn parse_stat_session(stat: &str) -> Option<i32> {
+    stat_field(stat, 6)?.parse().ok()
+}
+
+/// `starttime` (field 22) of a `/proc/<pid>/stat` line, in clock ticks after boot
+pub fn parse_stat_starttime(stat: &str) -> Option<u64> {
+    stat_field(stat, 22)?.parse().ok()
+}
+
+/// `utime` plus `stime` (fields 14 and 15) of a `/proc/<pid>/stat` line, in clock ticks
+pub fn parse_stat_cpu_ticks(stat: &str) -> Option<u64> {
+    let utime: u64 = stat_field(stat, 14)?.parse().ok()?;
+    let stime: u64 = stat_field(stat, 15)?.parse().ok()?;
+    Some(utime + stime)
+}
+
+/// `comm` (field 2) of a `/proc/<pid>/stat` line, without its parentheses
+pub fn parse_stat_comm(stat: &str) -> Option<&str> {
+    stat.get(stat.find('(')? + 1..stat.rfind(')')?)
+}
+
+/// `btime` of `/proc/stat`: boot time as a UNIX timestamp
+pub fn parse_btime(content: &str) -> Option<u64> {
+    content.lines().find_map(|line| line.strip_prefix("btime "))?.trim().parse().ok()
+}
+
+/// Clock ticks per second, the unit of `starttime`, `utime` and `stime`
+pub fn clock_ticks() -> u64 {
+    // SAFETY: sysconf only reads a configuration value
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks > 0 { ticks as u64 } else { 100 }
+}
+
+/// When a process with `starttime` ticks started, given the boot time
+pub fn started_at(boot_time: u64, starttime: u64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(boot_time as i64 * 1000 + (starttime * 1000 / clock_ticks()) as i64)
+}
+
+/// Field `number` (1-based, as in proc(5)) of a stat line, for fields after the command name
+fn stat_field(stat: &str, number: usize) -> Option<&str> {
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    after_comm.split_ascii_whitespace().nth(number.checked_sub(3)?)
+}
@@ -0,0 +1,114 @@
+//! How long a process has been running
+//!
+//! A process's start time is `starttime` from `/proc/<pid>/stat`, in clock
+//! ticks after boot, added to `btime` from `/proc/stat`. Processes are picked
+//! by PID or by name; a name matches the kernel's `comm` or the file name of
+//! the first command-line argument, so names longer than `comm`'s 15
+//! characters still match.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::proc_scan::{clock_ticks, parse_btime, parse_stat_comm, parse_stat_cpu_ticks, parse_stat_starttime, started_at, ProcScanner};
+
+/// Which processes to report on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessTarget {
+    /// One process by PID
+    Pid(u32),
+    /// Every process with this name, except runtime itself
+    Name(String),
+}
+
+impl ProcessTarget {
+    /// PIDs of the matching processes below `root` (normally `/`), in ascending order
+    ///
+    /// Fails if nothing matches, so a typo is not reported as an empty result.
+    pub fn find(&self, root: &Path) -> io::Result<Vec<u32>> {
+        let proc = root.join("proc");
+        match self {
+            ProcessTarget::Pid(pid) => {
+                if proc.join(pid.to_string()).join("stat").is_file() {
+                    Ok(vec![*pid])
+                } else {
+                    Err(io::Error::new(io::ErrorKind::NotFound, format!("no process with PID {}", pid)))
+                }
+            }
+            ProcessTarget::Name(name) => {
+                let own_pid = std::process::id();
+                let mut pids: Vec<u32> = ProcScanner::new(&proc)
+                    .pids()?
+                    .into_iter()
+                    .filter(|pid| *pid != own_pid && process_name_matches(&proc, *pid, name))
+                    .collect();
+                pids.sort_unstable();
+                if pids.is_empty() {
+                    return Err(io::Error::new(io::ErrorKind::NotFound, format!("no process named {}", name)));
+                }
+                Ok(pids)
+            }
+        }
+    }
+}
+
+/// Start time and CPU use of one process
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    /// `comm`, the name the kernel shows for the process
+    pub name: String,
+    /// When the process started
+    pub started: Option<DateTime<Utc>>,
+    /// Seconds since it started
+    pub uptime_seconds: Option<f64>,
+    /// User plus system CPU time
+    pub cpu_seconds: Option<f64>,
+}
+
+impl ProcessInfo {
+    /// Read the processes matching `target` below `root`, measuring uptime at `now`
+    ///
+    /// Processes that exit between matching and reading are left out.
+    pub fn read_all(target: &ProcessTarget, root: &Path, now: DateTime<Utc>) -> io::Result<Vec<Self>> {
+        let boot_time = fs::read_to_string(root.join("proc/stat")).ok().as_deref().and_then(parse_btime);
+        Ok(target.find(root)?
+            .into_iter()
+            .filter_map(|pid| {
+                let stat = fs::read_to_string(root.join(format!("proc/{}/stat", pid))).ok()?;
+                Some(Self::from_stat(pid, &stat, boot_time, now))
+            })
+            .collect())
+    }
+
+    /// A process from its `stat` line and the boot time
+    pub fn from_stat(pid: u32, stat: &str, boot_time: Option<u64>, now: DateTime<Utc>) -> Self {
+        let started = boot_time
+            .zip(parse_stat_starttime(stat))
+            .and_then(|(boot_time, starttime)| started_at(boot_time, starttime));
+
+        Self {
+            pid,
+            name: parse_stat_comm(stat).unwrap_or_default().to_string(),
+            started,
+            uptime_seconds: started.map(|started| ((now - started).num_milliseconds() as f64 / 1000.0).max(0.0)),
+            cpu_seconds: parse_stat_cpu_ticks(stat).map(|ticks| ticks as f64 / clock_ticks() as f64),
+        }
+    }
+}
+
+/// Whether `comm` or the file name of `argv[0]` is `name`
+fn process_name_matches(proc: &Path, pid: u32, name: &str) -> bool {
+    let dir = proc.join(pid.to_string());
+    let comm = fs::read_to_string(dir.join("stat")).ok();
+    if comm.as_deref().and_then(parse_stat_comm) == Some(name) {
+        return true;
+    }
+
+    let cmdline = fs::read(dir.join("cmdline")).unwrap_or_default();
+    let argv0 = cmdline.split(|byte| *byte == 0).next().unwrap_or_default();
+    let file_name = argv0.rsplit(|byte| *byte == b'/').next().unwrap_or_default();
+    !file_name.is_empty() && file_name == name.as_bytes()
+}
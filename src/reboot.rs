@@ -0,0 +1,225 @@
+//! Whether the host needs a reboot to run what is installed
+//!
+//! Two kinds of evidence are used. Package managers leave markers:
+//! Debian and Ubuntu write `/var/run/reboot-required` (with the packages that
+//! asked for it in `reboot-required.pkgs`), zypper writes
+//! `/run/reboot-needed`. Independently, a kernel newer than the running one
+//! under `/lib/modules` or `/boot`, or a running kernel whose modules were
+//! removed, means the upgrade has not been booted yet. Kernels are not
+//! compared inside containers, which run the host's kernel.
+
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::host_info::{detect_virtualization, Virtualization};
+
+/// Marker files written by package managers, relative to the root
+const MARKERS: &[&str] = &["var/run/reboot-required", "run/reboot-required", "run/reboot-needed"];
+
+/// Directories with one entry per installed kernel release, relative to the root
+const MODULE_DIRS: &[&str] = &["lib/modules", "usr/lib/modules"];
+
+/// Why a reboot is needed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RebootReason {
+    /// A newer kernel is installed than the one running
+    KernelUpgrade { running: String, installed: String },
+    /// The running kernel's modules are gone, usually after an upgrade
+    KernelRemoved { running: String },
+    /// A package manager left a reboot marker
+    Marker { path: String, packages: Vec<String> },
+}
+
+impl RebootReason {
+    /// Short description, e.g. `kernel 6.1.0-20-amd64 installed`
+    pub fn describe(&self) -> String {
+        match self {
+            RebootReason::KernelUpgrade { installed, .. } => format!("kernel {} installed", installed),
+            RebootReason::KernelRemoved { running } => format!("running kernel {} removed", running),
+            RebootReason::Marker { packages, .. } if !packages.is_empty() => {
+                format!("requested by {}", packages.join(", "))
+            }
+            RebootReason::Marker { path, .. } => format!("{} present", path),
+        }
+    }
+}
+
+/// Reboot status of the host
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RebootStatus {
+    /// Every reason found; empty if no reboot is needed
+    pub reasons: Vec<RebootReason>,
+    /// When the earliest evidence appeared, if known
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl RebootStatus {
+    /// Check the markers and kernels below `root` (normally `/`)
+    ///
+    /// Missing directories and files count as no evidence.
+    pub fn read(root: &Path) -> Self {
+        let mut status = Self::default();
+
+        // /var/run is normally a symlink to /run, so only the first marker found is reported
+        let marker = MARKERS.iter().find_map(|marker| fs::metadata(root.join(marker)).ok().map(|metadata| (marker, metadata)));
+        if let Some((marker, metadata)) = marker {
+            let mut packages: Vec<String> = Vec::new();
+            for package in fs::read_to_string(root.join(format!("{}.pkgs", marker))).unwrap_or_default().lines() {
+                let package = package.trim();
+                if !package.is_empty() && !packages.iter().any(|known| known == package) {
+                    packages.push(package.to_string());
+                }
+            }
+            let since = metadata.modified().ok().map(DateTime::from);
+            status.add(RebootReason::Marker { path: format!("/{}", marker), packages }, since);
+        }
+
+        // A container runs the host's kernel, so its own kernel packages say nothing
+        let in_container = matches!(detect_virtualization(root), Virtualization::Container(_));
+        if let Some(running) = running_kernel(root).filter(|_| !in_container) {
+            let installed = installed_kernels(root);
+            let newest = installed.iter().max_by(|a, b| compare_versions(&a.0, &b.0));
+
+            if let Some((newest, installed_at)) = newest {
+                if compare_versions(newest, &running) == Ordering::Greater {
+                    status.add(RebootReason::KernelUpgrade { running: running.clone(), installed: newest.clone() }, *installed_at);
+                } else if !installed.iter().any(|(release, _)| *release == running) {
+                    status.add(RebootReason::KernelRemoved { running }, None);
+                }
+            }
+        }
+
+        status
+    }
+
+    /// Check the running system
+    pub fn system() -> Self {
+        Self::read(Path::new("/"))
+    }
+
+    /// Whether any reason for a reboot was found
+    pub fn is_pending(&self) -> bool {
+        !self.reasons.is_empty()
+    }
+
+    /// `pending since 2024-03-01 10:00:00 (kernel 6.1.0-20-amd64 installed)` style summary
+    /// with the timestamp written by `format_time`
+    pub fn describe(&self, format_time: impl Fn(DateTime<Utc>) -> String) -> String {
+        if !self.is_pending() {
+            return "not pending".to_string();
+        }
+        let reasons = self.reasons.iter().map(RebootReason::describe).collect::<Vec<_>>().join("; ");
+        match self.since {
+            Some(since) => format!("pending since {} ({})", format_time(since), reasons),
+            None => format!("pending ({})", reasons),
+        }
+    }
+
+    fn add(&mut self, reason: RebootReason, since: Option<DateTime<Utc>>) {
+        self.reasons.push(reason);
+        self.since = match (self.since, since) {
+            (Some(current), Some(since)) => Some(current.min(since)),
+            (current, since) => current.or(since),
+        };
+    }
+}
+
+/// Release of the running kernel, from `osrelease` or else `/proc/version`
+fn running_kernel(root: &Path) -> Option<String> {
+    let read = |path: &str| fs::read_to_string(root.join(path)).ok();
+
+    read("proc/sys/kernel/osrelease")
+        .map(|release| release.trim().to_string())
+        .filter(|release| !release.is_empty())
+        .or_else(|| read("proc/version").as_deref().and_then(parse_proc_version))
+}
+
+/// Kernel release from a `/proc/version` line: `Linux version 6.1.0-18-amd64 (...) ...`
+pub fn parse_proc_version(version: &str) -> Option<String> {
+    version.strip_prefix("Linux version ")?
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+}
+
+/// Installed kernel releases with when they were installed
+fn installed_kernels(root: &Path) -> Vec<(String, Option<DateTime<Utc>>)> {
+    let mut kernels: Vec<(String, Option<DateTime<Utc>>)> = Vec::new();
+    let mut add = |release: String, installed: Option<DateTime<Utc>>| {
+        if !kernels.iter().any(|(known, _)| *known == release) {
+            kernels.push((release, installed));
+        }
+    };
+
+    for dir in MODULE_DIRS {
+        for entry in fs::read_dir(root.join(dir)).into_iter().flatten().flatten() {
+            // A modules directory without modules.dep is a leftover of a removed kernel
+            if !entry.path().join("modules.dep").exists() && !entry.path().join("kernel").is_dir() {
+                continue;
+            }
+            // depmod writes modules.dep when the kernel is installed
+            if let Some(release) = entry.file_name().to_str() {
+                add(release.to_string(), modified(&entry.path().join("modules.dep")).or_else(|| modified(&entry.path())));
+            }
+        }
+    }
+
+    // Arch names its images after the package (`vmlinuz-linux-lts`), not the
+    // release; only releases start with a digit
+    for entry in fs::read_dir(root.join("boot")).into_iter().flatten().flatten() {
+        let name = entry.file_name();
+        let Some(release) = name.to_str()
+            .and_then(|name| name.strip_prefix("vmlinuz-").or_else(|| name.strip_prefix("vmlinux-")))
+            .filter(|release| release.starts_with(|c: char| c.is_ascii_digit()))
+        else {
+            continue;
+        };
+        add(release.to_string(), modified(&entry.path()));
+    }
+
+    kernels
+}
+
+fn modified(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok().map(DateTime::from)
+}
+
+/// Compare kernel releases the way `sort -V` does: digit runs numerically,
+/// everything else byte by byte
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let mut a = chunks(a);
+    let mut b = chunks(b);
+
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    _ => x.cmp(y),
+                };
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+        }
+    }
+}
+
+/// Alternating runs of digits and non-digits
+fn chunks(version: &str) -> impl Iterator<Item = &str> {
+    let mut rest = version;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let digits = first.is_ascii_digit();
+        let end = rest.find(|c: char| c.is_ascii_digit() != digits).unwrap_or(rest.len());
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+        Some(chunk)
+    })
+}
@@ -0,0 +1,1091 @@
+//! Standalone renderers for every output format
+//!
+//! Each function turns a [`SystemMetrics`] snapshot into the exact text
+//! `Runtime` prints, so library users can format metrics without building
+//! `RuntimeArgs`.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+use crate::cgroup::{CgroupStats, Pressure};
+use crate::clock::Clock;
+use crate::duration::{DurationSettings, DurationStyle, DurationUnit};
+use crate::glyphs::{Glyphs, Icon};
+use crate::host_info::{HostInfo, OsRelease, Virtualization};
+use crate::load_trend::Trend;
+use crate::process::ProcessInfo;
+use crate::reboot::{RebootReason, RebootStatus};
+use crate::system_metrics::SystemMetrics;
+use crate::table::{visible_width, Table};
+use crate::theme::{self, ColorDepth, Painter, Role, Theme};
+use crate::time_format::TimeSettings;
+use crate::wtmp::{BootEnd, BootRecord};
+use crate::OutputFormat;
+
+/// Placeholder shown by human-readable formats for metrics that were not measured
+const UNKNOWN: &str = "unknown";
+
+/// Placeholder used by the Raw format for metrics that were not measured
+const RAW_UNKNOWN: &str = "-";
+
+/// When to emit ANSI color codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and the environment allows it
+    #[default]
+    Auto,
+    /// Always emit colors
+    Always,
+    /// Never emit colors
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether this policy emits colors in the current environment
+    pub fn enabled(&self) -> bool {
+        match self {
+            ColorChoice::Auto => theme::auto_color_enabled(),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// Which logged-in counts the Standard and Interactive formats show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountKind {
+    /// Distinct users
+    Users,
+    /// Distinct login sessions
+    Sessions,
+    /// Distinct terminals
+    Ttys,
+}
+
+impl CountKind {
+    /// The count from `metrics`, if measured
+    pub fn value(&self, metrics: &SystemMetrics) -> Option<usize> {
+        match self {
+            CountKind::Users => metrics.user_count(),
+            CountKind::Sessions => metrics.session_count(),
+            CountKind::Ttys => metrics.tty_count(),
+        }
+    }
+
+    /// Noun for `count` items, e.g. `1 user` or `3 ttys`
+    pub fn noun(&self, count: usize) -> &'static str {
+        match (self, count == 1) {
+            (CountKind::Users, true) => "user",
+            (CountKind::Users, false) => "users",
+            (CountKind::Sessions, true) => "session",
+            (CountKind::Sessions, false) => "sessions",
+            (CountKind::Ttys, true) => "tty",
+            (CountKind::Ttys, false) => "ttys",
+        }
+    }
+}
+
+impl FromStr for CountKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "users" | "user" => Ok(CountKind::Users),
+            "sessions" | "session" => Ok(CountKind::Sessions),
+            "ttys" | "tty" | "terminals" => Ok(CountKind::Ttys),
+            _ => Err(format!("unknown count '{}' (expected users, sessions or ttys)", s)),
+        }
+    }
+}
+
+/// A named column of the Csv, Tsv and KeyValue formats
+///
+/// [`Field::ALL`] lists Raw's columns in order, followed by the host
+/// identification, which Raw does not print.
+/// Durations are seconds with two decimals and timestamps UNIX seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    BootTime,
+    UptimeSeconds,
+    IdleSeconds,
+    Load1,
+    Load5,
+    Load15,
+    TasksRunnable,
+    TasksTotal,
+    LastPid,
+    ProcsRunning,
+    ProcsBlocked,
+    ContextSwitches,
+    Forks,
+    Hostname,
+    Kernel,
+    Os,
+    OsVersion,
+    Arch,
+    Virtualization,
+}
+
+impl Field {
+    /// Every field: Raw's columns in order, then the host identification
+    pub const ALL: [Field; 19] = [
+        Field::BootTime,
+        Field::UptimeSeconds,
+        Field::IdleSeconds,
+        Field::Load1,
+        Field::Load5,
+        Field::Load15,
+        Field::TasksRunnable,
+        Field::TasksTotal,
+        Field::LastPid,
+        Field::ProcsRunning,
+        Field::ProcsBlocked,
+        Field::ContextSwitches,
+        Field::Forks,
+        Field::Hostname,
+        Field::Kernel,
+        Field::Os,
+        Field::OsVersion,
+        Field::Arch,
+        Field::Virtualization,
+    ];
+
+    /// Column name, e.g. `uptime_seconds`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::BootTime => "boot_time",
+            Field::UptimeSeconds => "uptime_seconds",
+            Field::IdleSeconds => "idle_seconds",
+            Field::Load1 => "load1",
+            Field::Load5 => "load5",
+            Field::Load15 => "load15",
+            Field::TasksRunnable => "tasks_runnable",
+            Field::TasksTotal => "tasks_total",
+            Field::LastPid => "last_pid",
+            Field::ProcsRunning => "procs_running",
+            Field::ProcsBlocked => "procs_blocked",
+            Field::ContextSwitches => "context_switches",
+            Field::Forks => "forks",
+            Field::Hostname => "hostname",
+            Field::Kernel => "kernel",
+            Field::Os => "os",
+            Field::OsVersion => "os_version",
+            Field::Arch => "arch",
+            Field::Virtualization => "virtualization",
+        }
+    }
+
+    /// The value from `metrics`, if measured
+    pub fn value(&self, metrics: &SystemMetrics) -> Option<String> {
+        let loads = metrics.load_averages();
+        let tasks = metrics.tasks();
+        let scheduler = metrics.scheduler();
+        let host = metrics.host();
+        let os = host.and_then(|host| host.os.as_ref());
+
+        match self {
+            Field::BootTime => metrics.boot_time().map(|boot_time| boot_time.to_string()),
+            Field::UptimeSeconds => metrics.uptime_seconds().map(|uptime| format!("{:.2}", uptime)),
+            Field::IdleSeconds => metrics.idle_time().map(|idle| format!("{:.2}", idle)),
+            Field::Load1 => loads.map(|(load1, _, _)| format!("{:.2}", load1)),
+            Field::Load5 => loads.map(|(_, load5, _)| format!("{:.2}", load5)),
+            Field::Load15 => loads.map(|(_, _, load15)| format!("{:.2}", load15)),
+            Field::TasksRunnable => tasks.map(|tasks| tasks.runnable.to_string()),
+            Field::TasksTotal => tasks.map(|tasks| tasks.total.to_string()),
+            Field::LastPid => tasks.map(|tasks| tasks.last_pid.to_string()),
+            Field::ProcsRunning => scheduler.map(|scheduler| scheduler.procs_running.to_string()),
+            Field::ProcsBlocked => scheduler.map(|scheduler| scheduler.procs_blocked.to_string()),
+            Field::ContextSwitches => scheduler.map(|scheduler| scheduler.context_switches.to_string()),
+            Field::Forks => scheduler.map(|scheduler| scheduler.forks.to_string()),
+            Field::Hostname => host.and_then(|host| host.hostname.clone()),
+            Field::Kernel => host.and_then(|host| host.kernel_release.clone()),
+            Field::Os => os.map(|os| os.id.clone().unwrap_or_else(|| os.name.clone())),
+            Field::OsVersion => os.and_then(|os| os.version.clone()),
+            Field::Arch => host.and_then(|host| host.architecture.clone()),
+            Field::Virtualization => host
+                .and_then(|host| host.virtualization.as_ref())
+                .map(|virtualization| virtualization.kind().to_string()),
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Field::ALL
+            .into_iter()
+            .find(|field| field.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Field::ALL.iter().map(Field::name).collect();
+                format!("unknown field '{}' (expected one of {})", s, names.join(", "))
+            })
+    }
+}
+
+/// Which since-boot time the human-readable formats call uptime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UptimeClock {
+    /// Wall time since boot, including suspend, like `/proc/uptime`
+    #[default]
+    Boot,
+    /// Time since boot spent awake
+    Awake,
+}
+
+impl UptimeClock {
+    /// Seconds on this clock from `metrics`, if measured
+    pub fn seconds(&self, metrics: &SystemMetrics) -> Option<f64> {
+        match self {
+            UptimeClock::Boot => metrics.uptime_seconds(),
+            UptimeClock::Awake => metrics.awake_seconds(),
+        }
+    }
+}
+
+impl FromStr for UptimeClock {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "boot" => Ok(UptimeClock::Boot),
+            "awake" => Ok(UptimeClock::Awake),
+            _ => Err(format!("unknown uptime clock '{}' (expected boot or awake)", s)),
+        }
+    }
+}
+
+/// Options shared by all renderers
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RenderOptions {
+    /// Mark the output as coming from a container
+    pub show_container: bool,
+    /// Color policy for formats that use color
+    pub color: ColorChoice,
+    /// Clock used for the "current time" fields and to derive boot time
+    pub clock: Clock,
+    /// Time zone and format for every timestamp
+    pub time: TimeSettings,
+    /// Overrides for how uptimes are written
+    pub duration: DurationSettings,
+    /// Terminal width for table layouts; `None` if unknown
+    pub width: Option<usize>,
+    /// Colors for the Interactive dashboard
+    pub theme: Theme,
+    /// Colors the terminal supports; `None` detects it from the environment
+    pub color_depth: Option<ColorDepth>,
+    /// Border and icon characters for the Interactive dashboard
+    pub glyphs: Glyphs,
+    /// Logged-in counts to show; an empty list shows the user count
+    pub counts: Vec<CountKind>,
+    /// Whether uptime includes time spent suspended; Raw always does
+    pub uptime_clock: UptimeClock,
+    /// Columns of the Csv, Tsv and KeyValue formats; an empty list shows every field
+    pub fields: Vec<Field>,
+    /// Leave out the Csv and Tsv header line
+    pub omit_header: bool,
+}
+
+impl RenderOptions {
+    /// Logged-in counts to show, defaulting to the user count
+    pub fn counts(&self) -> &[CountKind] {
+        if self.counts.is_empty() {
+            &[CountKind::Users]
+        } else {
+            &self.counts
+        }
+    }
+
+    /// Columns of the tabular formats, defaulting to every field
+    pub fn fields(&self) -> &[Field] {
+        if self.fields.is_empty() {
+            &Field::ALL
+        } else {
+            &self.fields
+        }
+    }
+
+    /// Painter for the configured theme, color policy and color depth
+    pub fn painter(&self) -> Painter<'_> {
+        let depth = self.color_depth.unwrap_or_else(ColorDepth::detect);
+        Painter::new(&self.theme, depth, self.color.enabled())
+    }
+}
+
+/// Render metrics in the given output format
+pub fn render(format: &OutputFormat, metrics: &SystemMetrics, options: &RenderOptions) -> String {
+    match format {
+        OutputFormat::Standard => standard(metrics, options),
+        OutputFormat::Pretty => pretty(metrics, options),
+        OutputFormat::Raw => raw(metrics, options),
+        OutputFormat::Since => since(metrics, options),
+        OutputFormat::Interactive => interactive(metrics, options),
+        OutputFormat::Json => json(metrics, options),
+        OutputFormat::Prometheus => prometheus(metrics, options),
+        OutputFormat::Csv => csv(metrics, options),
+        OutputFormat::Tsv => tsv(metrics, options),
+        OutputFormat::KeyValue => key_value(metrics, options),
+    }
+}
+
+/// Raw numerical values: `boot_time uptime_seconds idle_time load1 load5 load15
+/// runnable_tasks total_tasks last_pid procs_running procs_blocked
+/// context_switches forks`
+///
+/// Unknown values are written as `-` so column positions stay stable. The
+/// first six columns are the original layout; the task and scheduler
+/// columns were appended after them, so scripts reading `$1` to `$6` are
+/// unaffected but `$NF` is now `forks` rather than `load15`. New columns are
+/// only ever added at the end. For a cgroup the columns are `start_time uptime_seconds cpu_seconds memory_bytes
+/// cpu_pressure10 cpu_pressure60 cpu_pressure300`, and for processes one line
+/// each of `pid start_time uptime_seconds cpu_seconds`.
+pub fn raw(metrics: &SystemMetrics, _options: &RenderOptions) -> String {
+    if let Some(cgroup) = metrics.cgroup() {
+        return raw_cgroup(cgroup);
+    }
+    if let Some(processes) = metrics.processes() {
+        return processes.iter().map(raw_process).collect::<Vec<_>>().join("\n");
+    }
+    let boot_time = metrics.boot_time()
+        .map_or_else(|| RAW_UNKNOWN.to_string(), |boot_time| boot_time.to_string());
+    let uptime_secs = metrics.uptime_seconds()
+        .map_or_else(|| RAW_UNKNOWN.to_string(), |uptime| format!("{:.6}", uptime));
+    let idle_time = metrics.idle_time()
+        .map_or_else(|| RAW_UNKNOWN.to_string(), |idle| idle.to_string());
+    let loads = match metrics.load_averages() {
+        Some((load1, load5, load15)) => format!("{:.2} {:.2} {:.2}", load1, load5, load15),
+        None => [RAW_UNKNOWN; 3].join(" "),
+    };
+    let tasks = match metrics.tasks() {
+        Some(tasks) => format!("{} {} {}", tasks.runnable, tasks.total, tasks.last_pid),
+        None => [RAW_UNKNOWN; 3].join(" "),
+    };
+    let scheduler = match metrics.scheduler() {
+        Some(scheduler) => format!("{} {} {} {}",
+            scheduler.procs_running, scheduler.procs_blocked, scheduler.context_switches, scheduler.forks),
+        None => [RAW_UNKNOWN; 4].join(" "),
+    };
+
+    format!("{} {} {} {} {} {}", boot_time, uptime_secs, idle_time, loads, tasks, scheduler)
+}
+
+fn raw_cgroup(cgroup: &CgroupStats) -> String {
+    let known = |value: Option<String>| value.unwrap_or_else(|| RAW_UNKNOWN.to_string());
+    let pressure = match cgroup.cpu_pressure {
+        Some(pressure) => format!("{:.2} {:.2} {:.2}", pressure.avg10, pressure.avg60, pressure.avg300),
+        None => [RAW_UNKNOWN; 3].join(" "),
+    };
+
+    format!("{} {} {} {} {}",
+        known(cgroup.started.map(|started| started.timestamp().to_string())),
+        known(cgroup.uptime_seconds.map(|uptime| format!("{:.6}", uptime))),
+        known(cgroup.cpu_seconds.map(|cpu| format!("{:.6}", cpu))),
+        known(cgroup.memory_bytes.map(|memory| memory.to_string())),
+        pressure)
+}
+
+fn raw_process(process: &ProcessInfo) -> String {
+    let known = |value: Option<String>| value.unwrap_or_else(|| RAW_UNKNOWN.to_string());
+
+    format!("{} {} {} {}",
+        process.pid,
+        known(process.started.map(|started| started.timestamp().to_string())),
+        known(process.uptime_seconds.map(|uptime| format!("{:.6}", uptime))),
+        known(process.cpu_seconds.map(|cpu| format!("{:.6}", cpu))))
+}
+
+/// Comma-separated values: a header line with the field names, then the values
+///
+/// Unknown values are empty cells.
+pub fn csv(metrics: &SystemMetrics, options: &RenderOptions) -> String {
+    delimited(metrics, options, ",")
+}
+
+/// Tab-separated values, laid out like [`csv`]
+pub fn tsv(metrics: &SystemMetrics, options: &RenderOptions) -> String {
+    delimited(metrics, options, "\t")
+}
+
+fn delimited(metrics: &SystemMetrics, options: &RenderOptions, separator: &str) -> String {
+    let fields = options.fields();
+    let values: Vec<String> = fields.iter()
+        .map(|field| delimited_value(field.value(metrics).unwrap_or_default(), separator))
+        .collect();
+    if options.omit_header {
+        return values.join(separator);
+    }
+    let names: Vec<&str> = fields.iter().map(Field::name).collect();
+
+    format!("{}\n{}", names.join(separator), values.join(separator))
+}
+
+/// Quote a value containing the separator, a quote or a line break, as RFC 4180 does
+fn delimited_value(value: String, separator: &str) -> String {
+    if value.contains(separator) || value.contains(['"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Shell assignments, one per field: `RUNTIME_UPTIME_SECONDS=3832.88`
+///
+/// Unknown values are empty. Anything but a plain word or number is single
+/// quoted, so `eval "$(runtime --env)"` is safe.
+pub fn key_value(metrics: &SystemMetrics, options: &RenderOptions) -> String {
+    options.fields()
+        .iter()
+        .map(|field| format!("RUNTIME_{}={}", field.name().to_uppercase(), shell_value(&field.value(metrics).unwrap_or_default())))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `value` as a shell word: bare if it has no special characters, else single quoted
+fn shell_value(value: &str) -> String {
+    if value.chars().all(|c| c.is_ascii_alphanumeric() || "._-+:/@%,".contains(c)) {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Human-readable uptime: `up 2 weeks, 3 days, 4 hours, 5 minutes`
+///
+/// For a cgroup or process the line starts with its name:
+/// `nginx.service up 3 days, 4 hours` or `nginx (812) up 3 days, 4 hours`.
+/// Several processes are listed as a table.
+pub fn pretty(metrics: &SystemMetrics, options: &RenderOptions) -> String {
+    let long = |seconds: Option<f64>| match seconds {
+        Some(seconds) => options.duration.resolve(DurationStyle::Long).format(seconds),
+        None => UNKNOWN.to_string(),
+    };
+    if let Some(cgroup) = metrics.cgroup() {
+        return format!("{} up {}", cgroup.name, long(cgroup.uptime_seconds));
+    }
+    match metrics.processes() {
+        Some([process]) => return format!("{} ({}) up {}", process.name, process.pid, long(process.uptime_seconds)),
+        Some(processes) => return process_table(processes, options),
+        None => {}
+    }
+    match options.uptime_clock.seconds(metrics) {
+        Some(uptime_secs) => format!("up {}", options.duration.resolve(DurationStyle::Long).format(uptime_secs)),
+        None => format!("up {}", UNKNOWN),
+    }
+}
+
+/// Boot timestamp, or a cgroup's or process's start time: `YYYY-MM-DD HH:MM:SS`
+///
+/// Several processes are listed as a table.
+pub fn since(metrics: &SystemMetrics, options: &RenderOptions) -> String {
+    let started = match (metrics.cgroup(), metrics.processes()) {
+        (Some(cgroup), _) => cgroup.started,
+        (None, Some([process])) => process.started,
+        (None, Some(processes)) => return process_table(processes, options),
+        (None, None) => boot_datetime(metrics),
+    };
+    match started {
+        Some(datetime) => options.time.format(datetime, "%Y-%m-%d %H:%M:%S"),
+        None => UNKNOWN.to_string(),
+    }
+}
+
+/// Classic uptime line with time, uptime, users and load averages
+///
+/// For a cgroup the users and load averages give way to the main PID, CPU
+/// time, memory and CPU pressure:
+/// ` 10:00:00 nginx.service up 3 days,  4:12, pid 812, cpu 1h 2m 3s, memory 120.5 MiB, cpu pressure: 0.12, 0.08, 0.05`.
+/// A process shows its CPU time: ` 10:00:00 nginx (812) up 3 days,  4:12, cpu 1h 2m 3s`;
+/// several processes are listed as a table.
+pub fn standard(metrics: &SystemMetrics, options: &RenderOptions) -> String {
+    let time_str = options.time.format(options.clock.now(), "%H:%M:%S");
+    if let Some(cgroup) = metrics.cgroup() {
+        return format!(" {} {}", time_str, standard_cgroup(cgroup, options));
+    }
+    match metrics.processes() {
+        Some([process]) => {
+            let known = |value: Option<String>| value.unwrap_or_else(|| UNKNOWN.to_string());
+            return format!(" {} {} ({}) up {}, cpu {}",
+                time_str,
+                process.name,
+                process.pid,
+                known(process.uptime_seconds.map(|uptime| options.duration.resolve(DurationStyle::Procps).format(uptime))),
+                known(process.cpu_seconds.map(|cpu| options.duration.resolve(DurationStyle::Compact).format(cpu))));
+        }
+        Some(processes) => return process_table(processes, options),
+        None => {}
+    }
+
+    let uptime_str = match options.uptime_clock.seconds(metrics) {
+        Some(uptime_secs) => options.duration.resolve(DurationStyle::Procps).format(uptime_secs),
+        None => UNKNOWN.to_string(),
+    };
+
+    let user_str = options.counts()
+        .iter()
+        .map(|kind| match kind.value(metrics) {
+            Some(count) => format!("{} {}", count, kind.noun(count)),
+            None => format!("{} {}", UNKNOWN, kind.noun(0)),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let load_str = match metrics.load_averages() {
+        Some((load1, load5, load15)) => format!("{:.2}, {:.2}, {:.2}", load1, load5, load15),
+        None => UNKNOWN.to_string(),
+    };
+
+    let container_suffix = if options.show_container { " (container)" } else { "" };
+
+    format!(" {} up {}{}, {}, load average: {}",
+        time_str, uptime_str, container_suffix, user_str, load_str)
+}
+
+fn standard_cgroup(cgroup: &CgroupStats, options: &RenderOptions) -> String {
+    let known = |value: Option<String>| value.unwrap_or_else(|| UNKNOWN.to_string());
+
+    format!("{} up {}, pid {}, cpu {}, memory {}, cpu pressure: {}",
+        cgroup.name,
+        known(cgroup.uptime_seconds.map(|uptime| options.duration.resolve(DurationStyle::Procps).format(uptime))),
+        known(cgroup.main_pid.map(|pid| pid.to_string())),
+        known(cgroup.cpu_seconds.map(|cpu| options.duration.resolve(DurationStyle::Compact).format(cpu))),
+        known(cgroup.memory_bytes.map(format_bytes)),
+        known(cgroup.cpu_pressure.map(|pressure| format!("{:.2}, {:.2}, {:.2}", pressure.avg10, pressure.avg60, pressure.avg300))))
+}
+
+/// Dashboard table sized to the content and the terminal width
+pub fn interactive(metrics: &SystemMetrics, options: &RenderOptions) -> String {
+    let painter = options.painter();
+
+    let uptime_fancy = uptime_fancy(metrics, options);
+    let load_fancy = match metrics.load_trend() {
+        Some(load_trend) => format!("{}  {}", load_fancy(metrics, options), trend_fancy(load_trend.trend, options)),
+        None => load_fancy(metrics, options),
+    };
+    let users = options.counts()
+        .iter()
+        .map(|kind| match kind.value(metrics) {
+            Some(count) => format!("{} {}",
+                painter.paint(Role::Count, count),
+                painter.paint(Role::Muted, kind.noun(count))),
+            None if options.counts().len() == 1 => painter.paint(Role::Muted, UNKNOWN),
+            None => painter.paint(Role::Muted, format!("{} {}", UNKNOWN, kind.noun(0))),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let boot_datetime = match boot_datetime(metrics) {
+        Some(datetime) => painter.paint(Role::Value, options.time.format(datetime, "%Y-%m-%d %H:%M:%S")),
+        None => painter.paint(Role::Muted, UNKNOWN),
+    };
+
+    let current_time = options.time.format(options.clock.now(), "%H:%M:%S %Z");
+
+    let in_container = matches!(metrics.host().and_then(|host| host.virtualization.as_ref()), Some(Virtualization::Container(_)));
+    let container_status = if options.show_container || in_container {
+        painter.paint(Role::Container, "[CONTAINER]")
+    } else {
+        painter.paint(Role::Native, "[NATIVE]")
+    };
+
+    let title = format!("{}  SYSTEM UPTIME DASHBOARD  {}", painter.paint(Role::Title, "*"), painter.paint(Role::Title, "*"));
+
+    let glyphs = options.glyphs;
+    let uptime_label = match options.uptime_clock {
+        UptimeClock::Boot => "System Uptime",
+        UptimeClock::Awake => "Awake Time",
+    };
+
+    let mut table = Table::new(title).glyphs(glyphs);
+    if let Some(host) = metrics.host() {
+        let known = |value: Option<String>| match value {
+            Some(value) => painter.paint(Role::Value, value),
+            None => painter.paint(Role::Muted, UNKNOWN),
+        };
+        let kernel = match (&host.kernel_release, &host.architecture) {
+            (Some(release), Some(architecture)) => Some(format!("{} ({})", release, architecture)),
+            (release, architecture) => release.clone().or(architecture.clone()),
+        };
+
+        table = table
+            .row(glyphs.label(Icon::Host, "Hostname"), known(host.hostname.clone()))
+            .row(glyphs.label(Icon::Os, "Operating System"), known(host.os.as_ref().map(OsRelease::display_name)))
+            .row(glyphs.label(Icon::Kernel, "Kernel"), known(kernel))
+            .row(glyphs.label(Icon::Virtualization, "Virtualization"),
+                known(host.virtualization.as_ref().map(Virtualization::describe)))
+            .separator();
+    }
+    if let Some(cgroup) = metrics.cgroup() {
+        let known = |value: Option<String>| match value {
+            Some(value) => painter.paint(Role::Value, value),
+            None => painter.paint(Role::Muted, UNKNOWN),
+        };
+        let memory = cgroup.memory_bytes.map(|memory| match cgroup.memory_max {
+            Some(max) => format!("{} of {}", format_bytes(memory), format_bytes(max)),
+            None => format_bytes(memory),
+        });
+        let pressure = [("cpu", cgroup.cpu_pressure), ("memory", cgroup.memory_pressure), ("io", cgroup.io_pressure)]
+            .iter()
+            .filter_map(|(resource, pressure)| pressure.map(|pressure| format!("{} {:.2}%", resource, pressure.avg10)))
+            .collect::<Vec<_>>();
+
+        table = table
+            .row(glyphs.label(Icon::Unit, "Unit"), painter.paint(Role::Value, &cgroup.name))
+            .row(glyphs.label(Icon::Uptime, "Unit Uptime"), duration_fancy(cgroup.uptime_seconds, options))
+            .row(glyphs.label(Icon::Boot, "Main PID"), known(cgroup.main_pid.map(|pid| pid.to_string())))
+            .row(glyphs.label(Icon::Cpu, "CPU Time"), duration_fancy(cgroup.cpu_seconds, options))
+            .row(glyphs.label(Icon::Memory, "Memory"), known(memory))
+            .row(glyphs.label(Icon::Load, "Pressure"), known((!pressure.is_empty()).then(|| pressure.join(", "))))
+            .separator();
+    }
+    if let Some(processes) = metrics.processes() {
+        for process in processes {
+            let label = format!("{} ({})", process.name, process.pid);
+            table = table.row(glyphs.label(Icon::Process, &label), duration_fancy(process.uptime_seconds, options));
+        }
+        table = table.separator();
+    }
+
+    table = table
+        .row(glyphs.label(Icon::Time, "Current Time"), painter.paint(Role::Value, current_time))
+        .row(glyphs.label(Icon::Uptime, uptime_label), uptime_fancy);
+    if let Some(suspended) = metrics.suspended_seconds().filter(|suspended| *suspended >= 1.0) {
+        table = table.row(glyphs.label(Icon::Suspend, "Suspended"), duration_fancy(Some(suspended), options));
+    }
+    table = table.row(glyphs.label(Icon::Boot, "Boot Time"), boot_datetime);
+    if let Some(previous) = metrics.previous_boot() {
+        table = table.row(glyphs.label(Icon::History, "Last Shutdown"), last_shutdown(previous, options));
+    }
+    if let Some(reboot) = metrics.reboot().filter(|reboot| reboot.is_pending()) {
+        let description = reboot.describe(|since| options.time.format(since, "%Y-%m-%d %H:%M:%S"));
+        table = table.row(glyphs.label(Icon::Reboot, "Reboot"), painter.paint(Role::LoadHigh, description));
+    }
+
+    table = table
+        .row(glyphs.label(Icon::Users, "Active Users"), users)
+        .row(glyphs.label(Icon::Load, "Load Average"), load_fancy);
+    if let Some(tasks) = metrics.tasks() {
+        let description = format!("{} runnable of {}, last PID {}", tasks.runnable, tasks.total, tasks.last_pid);
+        table = table.row(glyphs.label(Icon::Process, "Tasks"), painter.paint(Role::Value, description));
+    }
+    if let Some(scheduler) = metrics.scheduler() {
+        let description = format!("{} running, {} blocked, {} switches, {} forks",
+            scheduler.procs_running, scheduler.procs_blocked,
+            format_count(scheduler.context_switches), format_count(scheduler.forks));
+        table = table.row(glyphs.label(Icon::Cpu, "Scheduler"), painter.paint(Role::Value, description));
+    }
+
+    table
+        .row(glyphs.label(Icon::Mode, "System Mode"), container_status)
+        .render(options.width, |border| painter.paint(Role::Border, border))
+}
+
+/// Metrics as a JSON object; unknown values are `null`
+///
+/// Keys are stable: new fields may be added, existing ones keep their names
+/// and types.
+pub fn json(metrics: &SystemMetrics, options: &RenderOptions) -> String {
+    let timestamp = |instant: DateTime<Utc>| instant.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+
+    let value = serde_json::json!({
+        "time": timestamp(options.clock.now()),
+        "uptime_seconds": metrics.uptime_seconds(),
+        "idle_seconds": metrics.idle_time(),
+        "awake_seconds": metrics.awake_seconds(),
+        "suspended_seconds": metrics.suspended_seconds(),
+        "boot_time": boot_datetime(metrics).map(timestamp),
+        "users": metrics.user_count(),
+        "sessions": metrics.session_count(),
+        "ttys": metrics.tty_count(),
+        "load": metrics.load_averages().map(|(load1, load5, load15)| [load1, load5, load15]),
+        "load_trend": metrics.load_trend().map(|load_trend| serde_json::json!({
+            "trend": load_trend.trend.label(),
+            "short_term": (load_trend.short_term * 100.0).round() / 100.0,
+            "long_term": (load_trend.long_term * 100.0).round() / 100.0,
+        })),
+        "tasks": metrics.tasks().map(|tasks| serde_json::json!({
+            "runnable": tasks.runnable,
+            "total": tasks.total,
+            "last_pid": tasks.last_pid,
+        })),
+        "scheduler": metrics.scheduler().map(|scheduler| serde_json::json!({
+            "procs_running": scheduler.procs_running,
+            "procs_blocked": scheduler.procs_blocked,
+            "context_switches": scheduler.context_switches,
+            "forks": scheduler.forks,
+        })),
+        "container": options.show_container,
+        "host": metrics.host().map(host_json),
+        "reboot": metrics.reboot().map(|reboot| reboot_json(reboot, timestamp)),
+        "cgroup": metrics.cgroup().map(|cgroup| cgroup_json(cgroup, timestamp)),
+        "processes": metrics.processes().map(|processes| {
+            processes.iter()
+                .map(|process| serde_json::json!({
+                    "pid": process.pid,
+                    "name": process.name,
+                    "started": process.started.map(timestamp),
+                    "uptime_seconds": process.uptime_seconds,
+                    "cpu_seconds": process.cpu_seconds,
+                }))
+                .collect::<Vec<_>>()
+        }),
+    });
+
+    serde_json::to_string_pretty(&value).expect("JSON values always serialize")
+}
+
+/// Metrics in the Prometheus text exposition format, e.g. for the node
+/// exporter's textfile collector
+///
+/// Unknown metrics are left out instead of being reported as zero. A cgroup or
+/// processes reported on get their own series, labeled with the unit name or
+/// the PID and command.
+pub fn prometheus(metrics: &SystemMetrics, _options: &RenderOptions) -> String {
+    let mut out = String::new();
+    let mut family = |name: &str, kind: &str, help: &str, samples: Vec<(String, f64)>| {
+        if samples.is_empty() {
+            return;
+        }
+        out.push_str(&format!("# HELP runtime_{} {}\n# TYPE runtime_{} {}\n", name, help, name, kind));
+        for (labels, value) in samples {
+            out.push_str(&format!("runtime_{}{} {}\n", name, labels, value));
+        }
+    };
+    let host = |value: Option<f64>| value.map(|value| (String::new(), value)).into_iter().collect::<Vec<_>>();
+    let loads = metrics.load_averages();
+    let tasks = metrics.tasks();
+    let scheduler = metrics.scheduler();
+
+    family("uptime_seconds", "gauge", "Time since boot, including time suspended.", host(metrics.uptime_seconds()));
+    family("idle_seconds", "gauge", "Time all CPUs spent idle since boot.", host(metrics.idle_time()));
+    family("awake_seconds", "gauge", "Time since boot spent awake.", host(metrics.awake_seconds()));
+    family("suspended_seconds", "gauge", "Time since boot spent suspended.", host(metrics.suspended_seconds()));
+    family("boot_time_seconds", "gauge", "Boot time as a UNIX timestamp.", host(metrics.boot_time().map(|boot| boot as f64)));
+    family("users", "gauge", "Logged-in users.", host(metrics.user_count().map(|count| count as f64)));
+    family("sessions", "gauge", "Login sessions.", host(metrics.session_count().map(|count| count as f64)));
+    family("ttys", "gauge", "Terminals with a user attached.", host(metrics.tty_count().map(|count| count as f64)));
+    family("load1", "gauge", "1-minute load average.", host(loads.map(|(load1, _, _)| load1)));
+    family("load5", "gauge", "5-minute load average.", host(loads.map(|(_, load5, _)| load5)));
+    family("load15", "gauge", "15-minute load average.", host(loads.map(|(_, _, load15)| load15)));
+    family("tasks_runnable", "gauge", "Runnable tasks from /proc/loadavg.", host(tasks.map(|tasks| tasks.runnable as f64)));
+    family("tasks", "gauge", "Tasks (processes and threads) from /proc/loadavg.", host(tasks.map(|tasks| tasks.total as f64)));
+    family("last_pid", "gauge", "PID most recently handed out.", host(tasks.map(|tasks| tasks.last_pid as f64)));
+    family("procs_running", "gauge", "Processes running or waiting for a CPU.",
+        host(scheduler.map(|scheduler| scheduler.procs_running as f64)));
+    family("procs_blocked", "gauge", "Processes blocked waiting for I/O.",
+        host(scheduler.map(|scheduler| scheduler.procs_blocked as f64)));
+    family("context_switches_total", "counter", "Context switches since boot.",
+        host(scheduler.map(|scheduler| scheduler.context_switches as f64)));
+    family("forks_total", "counter", "Processes and threads created since boot.",
+        host(scheduler.map(|scheduler| scheduler.forks as f64)));
+    family("reboot_pending", "gauge", "Whether installed updates are waiting for a reboot.",
+        host(metrics.reboot().map(|reboot| if reboot.is_pending() { 1.0 } else { 0.0 })));
+    if metrics.host().is_some() {
+        let labels: Vec<String> = [
+            ("hostname", Field::Hostname),
+            ("kernel", Field::Kernel),
+            ("os", Field::Os),
+            ("version", Field::OsVersion),
+            ("arch", Field::Arch),
+            ("virtualization", Field::Virtualization),
+        ]
+            .into_iter()
+            .map(|(label, field)| format!("{}=\"{}\"", label, prometheus_label(&field.value(metrics).unwrap_or_default())))
+            .collect();
+        family("host_info", "gauge", "Host identification; the value is always 1.", vec![(format!("{{{}}}", labels.join(",")), 1.0)]);
+    }
+
+    if let Some(cgroup) = metrics.cgroup() {
+        let labeled = |value: Option<f64>| {
+            value.map(|value| (format!("{{unit=\"{}\"}}", prometheus_label(&cgroup.name)), value)).into_iter().collect()
+        };
+        family("cgroup_uptime_seconds", "gauge", "Time since the cgroup's oldest process started.", labeled(cgroup.uptime_seconds));
+        family("cgroup_cpu_seconds_total", "counter", "CPU time used by the cgroup.", labeled(cgroup.cpu_seconds));
+        family("cgroup_memory_bytes", "gauge", "Memory used by the cgroup.", labeled(cgroup.memory_bytes.map(|bytes| bytes as f64)));
+    }
+    if let Some(processes) = metrics.processes() {
+        let labeled = |value: fn(&ProcessInfo) -> Option<f64>| {
+            processes.iter()
+                .filter_map(|process| value(process).map(|value| {
+                    (format!("{{pid=\"{}\",name=\"{}\"}}", process.pid, prometheus_label(&process.name)), value)
+                }))
+                .collect()
+        };
+        family("process_uptime_seconds", "gauge", "Time since the process started.", labeled(|process| process.uptime_seconds));
+        family("process_cpu_seconds_total", "counter", "CPU time used by the process.", labeled(|process| process.cpu_seconds));
+    }
+
+    out
+}
+
+/// Escape a Prometheus label value
+fn prometheus_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn host_json(host: &HostInfo) -> serde_json::Value {
+    serde_json::json!({
+        "hostname": host.hostname,
+        "kernel": host.kernel_release,
+        "architecture": host.architecture,
+        "os": host.os.as_ref().map(|os| serde_json::json!({
+            "id": os.id,
+            "name": os.name,
+            "version": os.version,
+            "pretty_name": os.pretty_name,
+        })),
+        "virtualization": host.virtualization.as_ref().map(|virtualization| serde_json::json!({
+            "type": virtualization.kind(),
+            "name": virtualization.name(),
+        })),
+    })
+}
+
+fn reboot_json(reboot: &RebootStatus, timestamp: impl Fn(DateTime<Utc>) -> String) -> serde_json::Value {
+    let reasons: Vec<serde_json::Value> = reboot.reasons
+        .iter()
+        .map(|reason| match reason {
+            RebootReason::KernelUpgrade { running, installed } => serde_json::json!({
+                "kind": "kernel-upgrade",
+                "running": running,
+                "installed": installed,
+            }),
+            RebootReason::KernelRemoved { running } => serde_json::json!({
+                "kind": "kernel-removed",
+                "running": running,
+            }),
+            RebootReason::Marker { path, packages } => serde_json::json!({
+                "kind": "marker",
+                "path": path,
+                "packages": packages,
+            }),
+        })
+        .collect();
+
+    serde_json::json!({
+        "pending": reboot.is_pending(),
+        "since": reboot.since.map(timestamp),
+        "reasons": reasons,
+    })
+}
+
+fn cgroup_json(cgroup: &CgroupStats, timestamp: impl Fn(DateTime<Utc>) -> String) -> serde_json::Value {
+    let pressure = |pressure: Option<Pressure>| pressure.map(|pressure| [pressure.avg10, pressure.avg60, pressure.avg300]);
+
+    serde_json::json!({
+        "name": cgroup.name,
+        "path": cgroup.path.display().to_string(),
+        "main_pid": cgroup.main_pid,
+        "started": cgroup.started.map(timestamp),
+        "uptime_seconds": cgroup.uptime_seconds,
+        "cpu_seconds": cgroup.cpu_seconds,
+        "memory_bytes": cgroup.memory_bytes,
+        "memory_max_bytes": cgroup.memory_max,
+        "pressure": {
+            "cpu": pressure(cgroup.cpu_pressure),
+            "memory": pressure(cgroup.memory_pressure),
+            "io": pressure(cgroup.io_pressure),
+        },
+    })
+}
+
+/// How the previous boot ended: `clean, 2024-03-01 07:26:40` or `crash`
+pub fn last_shutdown(previous: &BootRecord, options: &RenderOptions) -> String {
+    let painter = options.painter();
+    let time = |instant| options.time.format(instant, "%Y-%m-%d %H:%M:%S");
+
+    match (previous.end, previous.last_seen) {
+        (BootEnd::Clean(at), _) => painter.paint(Role::Value, format!("clean, {}", time(at))),
+        (_, Some(last_seen)) => painter.paint(Role::LoadCritical, format!("crash, last seen {}", time(last_seen))),
+        _ => painter.paint(Role::LoadCritical, "crash (no shutdown record)"),
+    }
+}
+
+/// Boot history newest first, like `last -x reboot`
+///
+/// Boot IDs are shown when the boots were matched to a journal export.
+pub fn last_boots(boots: &[BootRecord], now: DateTime<Utc>, options: &RenderOptions) -> String {
+    let time = |instant| options.time.format(instant, "%Y-%m-%d %H:%M:%S");
+    let duration = options.duration.resolve(DurationStyle::Compact);
+    let with_ids = boots.iter().any(|boot| boot.boot_id.is_some());
+
+    let mut rows = vec![{
+        let mut header = vec!["STARTED".to_string(), "ENDED".to_string(), "DURATION".to_string(), "SHUTDOWN".to_string()];
+        if with_ids {
+            header.push("BOOT ID".to_string());
+        }
+        header
+    }];
+    for boot in boots.iter().rev() {
+        let ended = boot.ended();
+        let mut row = vec![
+            time(boot.started),
+            match (boot.end, ended) {
+                (BootEnd::Running, _) => "running".to_string(),
+                (_, Some(ended)) => time(ended),
+                (_, None) => UNKNOWN.to_string(),
+            },
+            match ended.or((boot.end == BootEnd::Running).then_some(now)) {
+                Some(ended) => duration.format((ended - boot.started).num_seconds() as f64),
+                None => UNKNOWN.to_string(),
+            },
+            match boot.end {
+                BootEnd::Running => "-".to_string(),
+                BootEnd::Clean(_) => "clean".to_string(),
+                BootEnd::Crash => "crash".to_string(),
+            },
+        ];
+        if with_ids {
+            row.push(boot.boot_id.clone().unwrap_or_else(|| "-".to_string()));
+        }
+        rows.push(row);
+    }
+
+    columns(&rows)
+}
+
+/// Processes as columns: `PID  COMMAND  STARTED  UPTIME  CPU`
+pub fn process_table(processes: &[ProcessInfo], options: &RenderOptions) -> String {
+    let known = |value: Option<String>| value.unwrap_or_else(|| UNKNOWN.to_string());
+    let duration = options.duration.resolve(DurationStyle::Compact);
+
+    let mut rows = vec![["PID", "COMMAND", "STARTED", "UPTIME", "CPU"].map(str::to_string).to_vec()];
+    for process in processes {
+        rows.push(vec![
+            process.pid.to_string(),
+            process.name.clone(),
+            known(process.started.map(|started| options.time.format(started, "%Y-%m-%d %H:%M:%S"))),
+            known(process.uptime_seconds.map(|uptime| duration.format(uptime))),
+            known(process.cpu_seconds.map(|cpu| duration.format(cpu))),
+        ]);
+    }
+
+    columns(&rows)
+}
+
+/// Rows as left-aligned columns two spaces apart; the first row is the header
+fn columns(rows: &[Vec<String>]) -> String {
+    let widths: Vec<usize> = (0..rows[0].len())
+        .map(|column| rows.iter().map(|row| visible_width(&row[column])).max().unwrap_or(0))
+        .collect();
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - visible_width(cell))))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// System uptime as a colored string such as `3d 4h 12m 5s`
+pub fn uptime_fancy(metrics: &SystemMetrics, options: &RenderOptions) -> String {
+    duration_fancy(options.uptime_clock.seconds(metrics), options)
+}
+
+/// A duration as a colored string
+///
+/// The compact style colors each unit; other styles are colored as a whole.
+fn duration_fancy(seconds: Option<f64>, options: &RenderOptions) -> String {
+    let painter = options.painter();
+    let Some(uptime_secs) = seconds else {
+        return painter.paint(Role::Muted, UNKNOWN);
+    };
+    let format = options.duration.resolve(DurationStyle::Compact);
+
+    if format.style != DurationStyle::Compact {
+        return painter.paint(Role::Value, format.format(uptime_secs));
+    }
+
+    format.parts(uptime_secs.max(0.0) as u64)
+        .iter()
+        .map(|(value, unit)| {
+            let role = match unit {
+                DurationUnit::Weeks => Role::Weeks,
+                DurationUnit::Days => Role::Days,
+                DurationUnit::Hours => Role::Hours,
+                DurationUnit::Minutes => Role::Minutes,
+                DurationUnit::Seconds => Role::Seconds,
+            };
+            format!("{}{}", painter.paint(role, value), unit.suffix())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Load averages with color coding based on system load
+pub fn load_fancy(metrics: &SystemMetrics, options: &RenderOptions) -> String {
+    let painter = options.painter();
+    let Some((load1, load5, load15)) = metrics.load_averages() else {
+        return painter.paint(Role::Muted, UNKNOWN);
+    };
+
+    let color_load = |load: f64| {
+        let role = if load < 1.0 {
+            Role::LoadLow
+        } else if load < 2.0 {
+            Role::LoadMedium
+        } else if load < 4.0 {
+            Role::LoadHigh
+        } else {
+            Role::LoadCritical
+        };
+        painter.paint(role, format!("{:.2}", load))
+    };
+
+    format!("{}, {}, {}",
+        color_load(load1),
+        color_load(load5),
+        color_load(load15)
+    )
+}
+
+/// Bytes in binary units: `512 B`, `120.5 MiB`
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Large counts in decimal units: `9876`, `91.2K`, `48.2M`
+fn format_count(count: u64) -> String {
+    const UNITS: [&str; 4] = ["K", "M", "G", "T"];
+    if count < 10_000 {
+        return count.to_string();
+    }
+    let mut value = count as f64 / 1000.0;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, UNITS[unit])
+}
+
+/// Load direction such as `↗ rising`, colored like the load it points to
+pub fn trend_fancy(trend: Trend, options: &RenderOptions) -> String {
+    let role = match trend {
+        Trend::Rising => Role::LoadHigh,
+        Trend::Falling => Role::LoadLow,
+        Trend::Steady => Role::Muted,
+    };
+    options.painter().paint(role, trend.describe(options.glyphs))
+}
+
+/// Boot time as a UTC timestamp, if it was measured
+fn boot_datetime(metrics: &SystemMetrics) -> Option<chrono::DateTime<chrono::Utc>> {
+    metrics.boot_time()
+        .and_then(|boot_time| chrono::DateTime::from_timestamp(boot_time as i64, 0))
+}
@@ -0,0 +1,89 @@
+//! Progress spinner shown while metrics collection is slow
+//!
+//! The spinner runs on its own thread and stays invisible until collection
+//! has taken longer than a delay, so fast collections print nothing extra.
+//! It draws on stderr and only when both stdout and stderr are terminals.
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use runtime::render::ColorChoice;
+use runtime::system_metrics::Progress;
+use runtime::theme::{ColorDepth, Painter, Role, Theme};
+
+const FRAMES: [&str; 4] = ["|", "/", "-", "\\"];
+const FRAME_INTERVAL: Duration = Duration::from_millis(80);
+
+/// Handle to a running spinner thread
+pub struct Spinner {
+    sender: Option<Sender<Progress>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Spinner {
+    /// Start a spinner that appears after `delay`, or a no-op when disabled
+    /// or when the output is not a terminal
+    ///
+    /// It is painted with `theme` unless `color` turns colors off.
+    pub fn start(delay: Duration, enabled: bool, theme: Theme, color: ColorChoice) -> Self {
+        if !enabled || !io::stdout().is_terminal() || !io::stderr().is_terminal() {
+            return Self { sender: None, thread: None };
+        }
+
+        let (sender, receiver) = mpsc::channel::<Progress>();
+        let thread = thread::spawn(move || {
+            let painter = Painter::new(&theme, ColorDepth::detect(), color.enabled());
+            let started = Instant::now();
+            let mut latest: Option<Progress> = None;
+            let mut frame = 0;
+            let mut drawn = false;
+
+            loop {
+                match receiver.recv_timeout(FRAME_INTERVAL) {
+                    Ok(progress) if progress.is_done() => break,
+                    Ok(progress) => latest = Some(progress),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                if started.elapsed() < delay {
+                    continue;
+                }
+
+                let step = latest.map_or_else(String::new, |progress| {
+                    format!(" ({}/{}: {})", progress.completed + 1, progress.total, progress.step)
+                });
+                eprint!("\r\x1b[2K{} {}{}",
+                    painter.paint(Role::Title, "Loading system metrics"),
+                    painter.paint(Role::LoadMedium, FRAMES[frame % FRAMES.len()]),
+                    painter.paint(Role::Muted, step));
+                let _ = io::stderr().flush();
+                frame += 1;
+                drawn = true;
+            }
+
+            if drawn {
+                eprint!("\r\x1b[2K");
+                let _ = io::stderr().flush();
+            }
+        });
+
+        Self { sender: Some(sender), thread: Some(thread) }
+    }
+
+    /// Report collection progress
+    pub fn update(&self, progress: Progress) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(progress);
+        }
+    }
+
+    /// Stop the spinner and erase it if it was drawn
+    pub fn finish(mut self) {
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
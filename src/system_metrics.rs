@@ -4,162 +4,385 @@
 //! /proc filesystem, matching the behavior of the standard uptime command.
 
 use std::fs;
-use std::io::{self, BufRead, BufReader};
-use std::collections::HashSet;
+use std::path::PathBuf;
+use std::io;
+use std::collections::{BTreeSet, HashSet};
+
+use crate::cgroup::{CgroupStats, CgroupTarget};
+use crate::clock::{Clock, KernelClock};
+use crate::host_info::HostInfo;
+use crate::load_trend::LoadTrend;
+use crate::reboot::RebootStatus;
+use crate::logind::{self, Logind};
+use crate::proc_scan::ProcScanner;
+use crate::process::{ProcessInfo, ProcessTarget};
+use crate::users::UserFilter;
+use crate::wtmp::{self, BootRecord};
 
 /// System metrics collector using low-level /proc filesystem access
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Every metric is optional: `None` means the value could not be measured
+/// (missing or unreadable /proc file, malformed content), which lets
+/// formatters say "unknown" instead of printing zeros that look real.
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct SystemMetrics {
     /// System uptime in seconds (floating point for precision)
-    uptime_seconds: f64,
+    uptime_seconds: Option<f64>,
     /// System idle time in seconds
-    idle_time: f64,
+    idle_time: Option<f64>,
+    /// Time since boot spent awake (`CLOCK_MONOTONIC`)
+    awake_seconds: Option<f64>,
+    /// Time since boot spent suspended or hibernating
+    suspended_seconds: Option<f64>,
     /// Load averages (1min, 5min, 15min)
-    load_avg: (f64, f64, f64),
+    load_avg: Option<(f64, f64, f64)>,
+    /// Runnable and total tasks and the last PID from /proc/loadavg
+    tasks: Option<TaskCounts>,
     /// Number of unique logged-in users
-    user_count: usize,
+    user_count: Option<usize>,
+    /// Number of distinct login sessions of those users
+    session_count: Option<usize>,
+    /// Number of distinct terminals those users are attached to
+    tty_count: Option<usize>,
     /// System boot time as UNIX timestamp
-    boot_time: u64,
+    boot_time: Option<u64>,
+    /// Cumulative CPU time counters from /proc/stat
+    cpu_times: Option<CpuTimes>,
+    /// Running and blocked processes, context switches and forks from /proc/stat
+    scheduler: Option<SchedulerStats>,
+    /// Memory totals from /proc/meminfo
+    memory: Option<MemoryInfo>,
+    /// Terminals with a logged-in user attached
+    sessions: Option<Vec<Session>>,
+    /// The boot before this one, from wtmp
+    previous_boot: Option<BootRecord>,
+    /// Hostname, kernel, OS and virtualization
+    host: Option<HostInfo>,
+    /// Whether installed updates are waiting for a reboot
+    reboot: Option<RebootStatus>,
+    /// The service or cgroup reported on instead of the host
+    cgroup: Option<CgroupStats>,
+    /// The processes reported on instead of the host
+    processes: Option<Vec<ProcessInfo>>,
 }
 
-impl Default for SystemMetrics {
-    fn default() -> Self {
-        Self {
-            uptime_seconds: 0.0,
-            idle_time: 0.0,
-            load_avg: (0.0, 0.0, 0.0),
-            user_count: 0,
-            boot_time: 0,
+/// Cumulative CPU time counters, in clock ticks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuTimes {
+    /// Ticks spent doing work (everything except idle and iowait)
+    pub busy: u64,
+    /// All ticks
+    pub total: u64,
+}
+
+impl CpuTimes {
+    /// CPU usage in percent between an earlier sample and this one
+    pub fn usage_since(&self, earlier: &CpuTimes) -> Option<f64> {
+        let total = self.total.checked_sub(earlier.total)?;
+        let busy = self.busy.checked_sub(earlier.busy)?;
+        if total == 0 {
+            return None;
         }
+        Some(busy as f64 * 100.0 / total as f64)
+    }
+}
+
+/// Scheduling entities from the fourth and fifth fields of /proc/loadavg
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskCounts {
+    /// Tasks currently runnable
+    pub runnable: u32,
+    /// Tasks (processes and threads) that exist
+    pub total: u32,
+    /// PID most recently handed out
+    pub last_pid: u32,
+}
+
+impl TaskCounts {
+    /// Parse /proc/loadavg content: `0.20 0.18 0.12 1/80 11206`
+    pub fn parse(loadavg: &str) -> Option<Self> {
+        let mut fields = loadavg.split_whitespace().skip(3);
+        let (runnable, total) = fields.next()?.split_once('/')?;
+
+        Some(Self {
+            runnable: runnable.parse().ok()?,
+            total: total.parse().ok()?,
+            last_pid: fields.next()?.parse().ok()?,
+        })
     }
 }
 
+/// Scheduler counters from /proc/stat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchedulerStats {
+    /// Tasks running or waiting for a CPU
+    pub procs_running: u64,
+    /// Tasks blocked waiting for I/O
+    pub procs_blocked: u64,
+    /// Context switches since boot
+    pub context_switches: u64,
+    /// Processes and threads created since boot
+    pub forks: u64,
+}
+
+impl SchedulerStats {
+    /// Parse the `procs_running`, `procs_blocked`, `ctxt` and `processes`
+    /// lines of /proc/stat; `None` unless all four are present
+    pub fn parse(stat: &str) -> Option<Self> {
+        let field = |name: &str| {
+            stat.lines()
+                .find(|line| line.split_whitespace().next() == Some(name))
+                .and_then(|line| line.split_whitespace().nth(1))
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+
+        Some(Self {
+            procs_running: field("procs_running")?,
+            procs_blocked: field("procs_blocked")?,
+            context_switches: field("ctxt")?,
+            forks: field("processes")?,
+        })
+    }
+}
+
+/// Memory totals in KiB
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryInfo {
+    pub total_kib: u64,
+    pub available_kib: u64,
+}
+
+impl MemoryInfo {
+    /// Memory in use, in percent of the total
+    pub fn used_percent(&self) -> f64 {
+        if self.total_kib == 0 {
+            return 0.0;
+        }
+        self.total_kib.saturating_sub(self.available_kib) as f64 * 100.0 / self.total_kib as f64
+    }
+}
+
+/// A user attached to a terminal
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Session {
+    pub uid: u32,
+    /// Terminal name without `/dev/`, e.g. `pts/0` or `tty1`
+    pub tty: String,
+}
+
 impl SystemMetrics {
     /// Creates a new SystemMetrics instance by reading from /proc filesystem
+    ///
+    /// Returns the first error encountered; use [`SystemMetrics::refresh`] on a
+    /// default instance to keep whatever could be measured despite errors.
     pub fn new() -> io::Result<Self> {
         let mut metrics = Self::default();
+        metrics.refresh()?;
+        Ok(metrics)
+    }
+
+    /// Read uptime and idle time from /proc/uptime, and awake and suspended
+    /// time from the kernel clocks
+    ///
+    /// /proc/uptime counts time spent suspended, so a laptop that sleeps
+    /// overnight reports days of uptime after a few hours of use.
+    fn read_uptime(&mut self, options: &CollectOptions) -> io::Result<()> {
+        self.uptime_seconds = None;
+        self.idle_time = None;
+        self.awake_seconds = None;
+        self.suspended_seconds = None;
+
+        if let Ok(reading) = options.kernel_clock.read() {
+            self.awake_seconds = Some(reading.monotonic);
+            self.suspended_seconds = Some(reading.suspended());
+        }
 
-        // Read uptime and idle time from /proc/uptime
-        metrics.read_uptime()?;
+        let content = fs::read_to_string("/proc/uptime")?;
+        let mut parts = content.split_whitespace();
 
-        // Read load averages from /proc/loadavg
-        metrics.read_loadavg()?;
+        self.uptime_seconds = parts.next().and_then(|s| s.parse().ok());
+        self.idle_time = parts.next().and_then(|s| s.parse().ok());
 
-        // Read user count from /proc/stat and utmp-like sources
-        metrics.read_users()?;
+        Ok(())
+    }
 
-        // Calculate boot time from uptime
-        metrics.calculate_boot_time()?;
+    /// Read load averages and task counts from /proc/loadavg
+    fn read_loadavg(&mut self) -> io::Result<()> {
+        self.load_avg = None;
+        self.tasks = None;
 
-        Ok(metrics)
+        let content = fs::read_to_string("/proc/loadavg")?;
+        self.tasks = TaskCounts::parse(&content);
+        let mut parts = content.split_whitespace().map(|s| s.parse::<f64>().ok());
+
+        if let (Some(Some(load1)), Some(Some(load5)), Some(Some(load15))) =
+            (parts.next(), parts.next(), parts.next())
+        {
+            self.load_avg = Some((load1, load5, load15));
+        }
+
+        Ok(())
     }
 
-    /// Read uptime and idle time from /proc/uptime
-    fn read_uptime(&mut self) -> io::Result<()> {
-        let content = fs::read_to_string("/proc/uptime")?;
-        let parts: Vec<&str> = content.trim().split_whitespace().collect();
+    /// Read aggregate CPU counters from the first line of /proc/stat, and
+    /// the scheduler counters further down
+    fn read_cpu_times(&mut self) -> io::Result<()> {
+        self.cpu_times = None;
+        self.scheduler = None;
+
+        let content = fs::read_to_string("/proc/stat")?;
+        self.scheduler = SchedulerStats::parse(&content);
+        let Some(line) = content.lines().find(|line| line.starts_with("cpu ")) else {
+            return Ok(());
+        };
+
+        // user nice system idle iowait irq softirq steal (guest time is already in user)
+        let ticks: Vec<u64> = line.split_whitespace()
+            .skip(1)
+            .take(8)
+            .filter_map(|field| field.parse().ok())
+            .collect();
+
+        if ticks.len() >= 5 {
+            let total: u64 = ticks.iter().sum();
+            let idle = ticks[3] + ticks[4];
+            self.cpu_times = Some(CpuTimes { busy: total - idle, total });
+        }
 
-        if parts.len() >= 2 {
-            self.uptime_seconds = parts[0].parse().unwrap_or(0.0);
-            self.idle_time = parts[1].parse().unwrap_or(0.0);
+        Ok(())
+    }
+
+    /// Read total and available memory from /proc/meminfo
+    fn read_meminfo(&mut self) -> io::Result<()> {
+        self.memory = None;
+
+        let content = fs::read_to_string("/proc/meminfo")?;
+        let field = |name: &str| {
+            content.lines()
+                .find(|line| line.starts_with(name))
+                .and_then(|line| line.split_whitespace().nth(1))
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+
+        if let (Some(total_kib), Some(available_kib)) = (field("MemTotal:"), field("MemAvailable:")) {
+            self.memory = Some(MemoryInfo { total_kib, available_kib });
         }
 
         Ok(())
     }
 
-    /// Read load averages from /proc/loadavg
-    fn read_loadavg(&mut self) -> io::Result<()> {
-        let content = fs::read_to_string("/proc/loadavg")?;
-        let parts: Vec<&str> = content.trim().split_whitespace().collect();
+    /// Identify the host; files that cannot be read leave their fields unknown
+    fn read_host(&mut self, options: &CollectOptions) -> io::Result<()> {
+        self.host = Some(HostInfo::read(&options.host_root));
+        Ok(())
+    }
 
-        if parts.len() >= 3 {
-            let load1 = parts[0].parse().unwrap_or(0.0);
-            let load5 = parts[1].parse().unwrap_or(0.0);
-            let load15 = parts[2].parse().unwrap_or(0.0);
-            self.load_avg = (load1, load5, load15);
+    /// Look for reboot markers and kernels newer than the running one
+    fn read_reboot(&mut self, options: &CollectOptions) -> io::Result<()> {
+        self.reboot = Some(RebootStatus::read(&options.host_root));
+        Ok(())
+    }
+
+    /// Read the cgroup in `options.cgroup`, if one was asked for
+    fn read_cgroup(&mut self, options: &CollectOptions, clock: &Clock) -> io::Result<()> {
+        self.cgroup = None;
+        if let Some(target) = &options.cgroup {
+            self.cgroup = Some(CgroupStats::read(target, &options.host_root, clock.now())?);
         }
+        Ok(())
+    }
 
+    /// Read the processes in `options.processes`, if any were asked for
+    fn read_processes(&mut self, options: &CollectOptions, clock: &Clock) -> io::Result<()> {
+        self.processes = None;
+        if let Some(target) = &options.processes {
+            self.processes = Some(ProcessInfo::read_all(target, &options.host_root, clock.now())?);
+        }
         Ok(())
     }
 
+    /// Find the previous boot and how it ended in wtmp
+    ///
+    /// A missing wtmp file means no history is kept, which is not an error.
+    fn read_boot_history(&mut self, options: &CollectOptions) -> io::Result<()> {
+        self.previous_boot = None;
+
+        let Some(path) = &options.wtmp_path else {
+            return Ok(());
+        };
+        let records = match wtmp::read_records(path) {
+            Ok(records) => records,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        self.previous_boot = wtmp::previous_boot(&wtmp::boots(&records)).cloned();
+        Ok(())
+    }
+
+    /// Take users, sessions and ttys from logind; false if it has no logins
+    fn read_logind_users(&mut self, logind: &Logind, filter: &UserFilter) -> bool {
+        let logins: Vec<_> = logind.logins().filter(|login| filter.matches(login.uid)).collect();
+        if logins.is_empty() {
+            return false;
+        }
+
+        let sessions: BTreeSet<Session> = logins
+            .iter()
+            .map(|login| Session {
+                uid: login.uid,
+                // Graphical logins have a seat instead of a terminal
+                tty: login.tty.clone()
+                    .or_else(|| login.seat.clone())
+                    .unwrap_or_else(|| format!("session {}", login.id)),
+            })
+            .collect();
+
+        self.user_count = Some(logins.iter().map(|login| login.uid).collect::<HashSet<_>>().len());
+        self.session_count = Some(logins.len());
+        self.tty_count = Some(logins.iter().filter_map(|login| login.tty.as_ref()).collect::<HashSet<_>>().len());
+        self.sessions = Some(sessions.into_iter().collect());
+        true
+    }
+
     /// Count unique users from multiple sources to match uptime behavior
-    fn read_users(&mut self) -> io::Result<()> {
-        let mut unique_users = HashSet::new();
+    fn read_users(&mut self, options: &CollectOptions) -> io::Result<()> {
+        let filter = &options.users;
+        self.user_count = None;
+        self.session_count = None;
+        self.tty_count = None;
+        self.sessions = None;
+
+        // Method 0: systemd-logind's session list, when it knows of any logins
+        if let Some(Ok(logind)) = options.logind_root.as_deref().map(Logind::read) {
+            if self.read_logind_users(&logind, filter) {
+                return Ok(());
+            }
+        }
 
-        // Method 1: Read from /proc/*/stat to find processes with ttys
-        if let Ok(entries) = fs::read_dir("/proc") {
-            for entry in entries.flatten() {
-                if let Some(name) = entry.file_name().to_str() {
-                    if name.chars().all(|c| c.is_ascii_digit()) {
-                        if let Ok(stat_content) = fs::read_to_string(entry.path().join("stat")) {
-                            let parts: Vec<&str> = stat_content.split_whitespace().collect();
-                            if parts.len() > 6 {
-                                let tty_nr: i32 = parts[6].parse().unwrap_or(0);
-                                if tty_nr > 0 {
-                                    // This process has a controlling terminal
-                                    if let Ok(status_content) = fs::read_to_string(entry.path().join("status")) {
-                                        for line in status_content.lines() {
-                                            if line.starts_with("Uid:") {
-                                                if let Some(uid_str) = line.split_whitespace().nth(1) {
-                                                    if let Ok(uid) = uid_str.parse::<u32>() {
-                                                        // Only count UIDs >= 1000 (regular users) or root (0)
-                                                        if uid >= 1000 || uid == 0 {
-                                                            unique_users.insert(uid);
-                                                        }
-                                                    }
-                                                }
-                                                break;
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+        let mut unique_users = HashSet::new();
+        let mut sessions = BTreeSet::new();
+        // Session leader PIDs; the fd fallback cannot see them
+        let mut session_ids = HashSet::new();
+        let is_user = |uid: u32| filter.matches(uid);
+
+        // Method 1: processes whose stat reports a controlling terminal
+        // An unreadable /proc means the count is unknown, not zero
+        let scanner = ProcScanner::new(options.host_root.join("proc"));
+        for process in scanner.terminal_processes()? {
+            if is_user(process.uid) {
+                unique_users.insert(process.uid);
+                sessions.insert(Session { uid: process.uid, tty: tty_name(process.tty_nr) });
+                session_ids.insert(process.session);
             }
         }
 
-        // Method 2: Fallback - count login sessions from /proc/*/fd/* pointing to ptys/ttys
+        // Method 2: Fallback - terminals open on the standard descriptors
         if unique_users.is_empty() {
-            if let Ok(entries) = fs::read_dir("/proc") {
-                for entry in entries.flatten() {
-                    if let Some(name) = entry.file_name().to_str() {
-                        if name.chars().all(|c| c.is_ascii_digit()) {
-                            let fd_dir = entry.path().join("fd");
-                            if let Ok(fd_entries) = fs::read_dir(&fd_dir) {
-                                let mut has_terminal = false;
-                                for fd_entry in fd_entries.flatten() {
-                                    if let Ok(link_target) = fs::read_link(fd_entry.path()) {
-                                        if let Some(target_str) = link_target.to_str() {
-                                            if target_str.starts_with("/dev/pts/") ||
-                                               target_str.starts_with("/dev/tty") {
-                                                has_terminal = true;
-                                                break;
-                                            }
-                                        }
-                                    }
-                                }
-
-                                if has_terminal {
-                                    if let Ok(status_content) = fs::read_to_string(entry.path().join("status")) {
-                                        for line in status_content.lines() {
-                                            if line.starts_with("Uid:") {
-                                                if let Some(uid_str) = line.split_whitespace().nth(1) {
-                                                    if let Ok(uid) = uid_str.parse::<u32>() {
-                                                        if uid >= 1000 || uid == 0 {
-                                                            unique_users.insert(uid);
-                                                        }
-                                                    }
-                                                }
-                                                break;
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+            for (uid, tty) in scanner.fd_terminals().unwrap_or_default() {
+                if is_user(uid) {
+                    unique_users.insert(uid);
+                    sessions.insert(Session { uid, tty });
                 }
             }
         }
@@ -170,66 +393,561 @@ impl SystemMetrics {
             let display_set = std::env::var("DISPLAY").is_ok();
             let wayland_set = std::env::var("WAYLAND_DISPLAY").is_ok();
 
-            if display_set || wayland_set {
+            if (display_set || wayland_set) && is_user(1000) {
                 unique_users.insert(1000); // Assume at least one regular user
             }
 
-            // Always count the current user if we can determine it
-            if let Ok(current_uid_str) = std::env::var("UID") {
-                if let Ok(uid) = current_uid_str.parse::<u32>() {
-                    unique_users.insert(uid);
-                }
+            // Whoever is running this command is logged in somewhere
+            // SAFETY: getuid has no preconditions and cannot fail
+            let uid = unsafe { libc::getuid() };
+            if is_user(uid) {
+                unique_users.insert(uid);
             }
+
+            // A guessed user has one session without a known terminal
+            self.user_count = Some(unique_users.len());
+            self.session_count = Some(unique_users.len());
+            self.tty_count = Some(0);
+            self.sessions = Some(Vec::new());
+            return Ok(());
         }
 
-        self.user_count = if unique_users.is_empty() { 1 } else { unique_users.len() };
+        self.user_count = Some(unique_users.len());
+        self.session_count = Some(if session_ids.is_empty() { sessions.len() } else { session_ids.len() });
+        self.tty_count = Some(sessions.iter().map(|session| &session.tty).collect::<HashSet<_>>().len());
+        self.sessions = Some(sessions.into_iter().collect());
         Ok(())
     }
 
     /// Calculate boot time from current time minus uptime
-    fn calculate_boot_time(&mut self) -> io::Result<()> {
-        use std::time::{SystemTime, UNIX_EPOCH};
+    fn calculate_boot_time(&mut self, clock: &Clock) -> io::Result<()> {
+        self.boot_time = None;
 
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
+        let now = u64::try_from(clock.unix_seconds())
+            .map_err(|_| io::Error::other("clock is before the UNIX epoch"))?;
 
-        self.boot_time = now.saturating_sub(self.uptime_seconds as u64);
+        self.boot_time = self.uptime_seconds
+            .map(|uptime| now.saturating_sub(uptime as u64));
         Ok(())
     }
 
-    /// Get uptime in seconds with decimal precision
-    pub fn uptime_seconds(&self) -> f64 {
+    /// Set the uptime in seconds, e.g. for metrics collected elsewhere
+    pub fn with_uptime_seconds(mut self, uptime_seconds: f64) -> Self {
+        self.uptime_seconds = Some(uptime_seconds);
+        self
+    }
+
+    /// Set the idle time in seconds
+    pub fn with_idle_time(mut self, idle_time: f64) -> Self {
+        self.idle_time = Some(idle_time);
+        self
+    }
+
+    /// Set the time since boot spent awake
+    pub fn with_awake_seconds(mut self, awake_seconds: f64) -> Self {
+        self.awake_seconds = Some(awake_seconds);
+        self
+    }
+
+    /// Set the time since boot spent suspended
+    pub fn with_suspended_seconds(mut self, suspended_seconds: f64) -> Self {
+        self.suspended_seconds = Some(suspended_seconds);
+        self
+    }
+
+    /// Set the load averages as (1min, 5min, 15min)
+    pub fn with_load_averages(mut self, load_avg: (f64, f64, f64)) -> Self {
+        self.load_avg = Some(load_avg);
+        self
+    }
+
+    /// Set the runnable and total task counts and the last PID
+    pub fn with_tasks(mut self, tasks: TaskCounts) -> Self {
+        self.tasks = Some(tasks);
+        self
+    }
+
+    /// Set the number of unique users
+    pub fn with_user_count(mut self, user_count: usize) -> Self {
+        self.user_count = Some(user_count);
+        self
+    }
+
+    /// Set the number of login sessions
+    pub fn with_session_count(mut self, session_count: usize) -> Self {
+        self.session_count = Some(session_count);
+        self
+    }
+
+    /// Set the number of terminals
+    pub fn with_tty_count(mut self, tty_count: usize) -> Self {
+        self.tty_count = Some(tty_count);
+        self
+    }
+
+    /// Set the boot time as UNIX timestamp
+    pub fn with_boot_time(mut self, boot_time: u64) -> Self {
+        self.boot_time = Some(boot_time);
+        self
+    }
+
+    /// Set the previous boot
+    pub fn with_previous_boot(mut self, previous_boot: BootRecord) -> Self {
+        self.previous_boot = Some(previous_boot);
+        self
+    }
+
+    /// Set the host identification
+    pub fn with_host(mut self, host: HostInfo) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    /// Set the pending-reboot status
+    pub fn with_reboot(mut self, reboot: RebootStatus) -> Self {
+        self.reboot = Some(reboot);
+        self
+    }
+
+    /// Set the stats of the service or cgroup reported on
+    pub fn with_cgroup(mut self, cgroup: CgroupStats) -> Self {
+        self.cgroup = Some(cgroup);
+        self
+    }
+
+    /// Set the processes reported on
+    pub fn with_processes(mut self, processes: Vec<ProcessInfo>) -> Self {
+        self.processes = Some(processes);
+        self
+    }
+
+    /// Set the cumulative CPU time counters
+    pub fn with_cpu_times(mut self, cpu_times: CpuTimes) -> Self {
+        self.cpu_times = Some(cpu_times);
+        self
+    }
+
+    /// Set the scheduler counters
+    pub fn with_scheduler(mut self, scheduler: SchedulerStats) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
+    /// Set the memory totals
+    pub fn with_memory(mut self, memory: MemoryInfo) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Set the terminal sessions
+    pub fn with_sessions(mut self, sessions: Vec<Session>) -> Self {
+        self.sessions = Some(sessions);
+        self
+    }
+
+    /// Get uptime in seconds with decimal precision, if measured
+    pub fn uptime_seconds(&self) -> Option<f64> {
         self.uptime_seconds
     }
 
-    /// Get idle time in seconds
-    pub fn idle_time(&self) -> f64 {
+    /// Get idle time in seconds, if measured
+    pub fn idle_time(&self) -> Option<f64> {
         self.idle_time
     }
 
-    /// Get load averages as (1min, 5min, 15min)
-    pub fn load_averages(&self) -> (f64, f64, f64) {
+    /// Get the time since boot spent awake, if measured
+    pub fn awake_seconds(&self) -> Option<f64> {
+        self.awake_seconds
+    }
+
+    /// Get the time since boot spent suspended or hibernating, if measured
+    pub fn suspended_seconds(&self) -> Option<f64> {
+        self.suspended_seconds
+    }
+
+    /// Get load averages as (1min, 5min, 15min), if measured
+    pub fn load_averages(&self) -> Option<(f64, f64, f64)> {
         self.load_avg
     }
 
-    /// Get number of unique users
-    pub fn user_count(&self) -> usize {
+    /// Direction of the load, derived from the three load averages
+    pub fn load_trend(&self) -> Option<LoadTrend> {
+        self.load_avg.map(LoadTrend::from_averages)
+    }
+
+    /// Get runnable and total task counts and the last PID, if measured
+    pub fn tasks(&self) -> Option<TaskCounts> {
+        self.tasks
+    }
+
+    /// Get number of unique users, if measured
+    pub fn user_count(&self) -> Option<usize> {
         self.user_count
     }
 
-    /// Get system boot time as UNIX timestamp
-    pub fn boot_time(&self) -> u64 {
+    /// Get number of login sessions, if measured
+    pub fn session_count(&self) -> Option<usize> {
+        self.session_count
+    }
+
+    /// Get number of terminals with a user attached, if measured
+    pub fn tty_count(&self) -> Option<usize> {
+        self.tty_count
+    }
+
+    /// Get the previous boot and how it ended, if wtmp records it
+    pub fn previous_boot(&self) -> Option<&BootRecord> {
+        self.previous_boot.as_ref()
+    }
+
+    /// Get the host identification, if collected
+    pub fn host(&self) -> Option<&HostInfo> {
+        self.host.as_ref()
+    }
+
+    /// Get the pending-reboot status, if checked
+    pub fn reboot(&self) -> Option<&RebootStatus> {
+        self.reboot.as_ref()
+    }
+
+    /// Get the stats of the service or cgroup reported on, if asked for
+    pub fn cgroup(&self) -> Option<&CgroupStats> {
+        self.cgroup.as_ref()
+    }
+
+    /// Get the processes reported on, if asked for
+    pub fn processes(&self) -> Option<&[ProcessInfo]> {
+        self.processes.as_deref()
+    }
+
+    /// Get system boot time as UNIX timestamp, if measured
+    pub fn boot_time(&self) -> Option<u64> {
         self.boot_time
     }
 
-    /// Refresh all metrics
+    /// Get cumulative CPU time counters, if measured
+    pub fn cpu_times(&self) -> Option<CpuTimes> {
+        self.cpu_times
+    }
+
+    /// Get running and blocked processes, context switches and forks, if measured
+    pub fn scheduler(&self) -> Option<SchedulerStats> {
+        self.scheduler
+    }
+
+    /// Get memory totals, if measured
+    pub fn memory(&self) -> Option<MemoryInfo> {
+        self.memory
+    }
+
+    /// Get terminals with a logged-in user attached, if measured
+    pub fn sessions(&self) -> Option<&[Session]> {
+        self.sessions.as_deref()
+    }
+
+    /// Refresh all metrics using the system clock
     pub fn refresh(&mut self) -> io::Result<()> {
-        self.read_uptime()?;
-        self.read_loadavg()?;
-        self.read_users()?;
-        self.calculate_boot_time()?;
-        Ok(())
+        self.refresh_with_clock(&Clock::System)
+    }
+
+    /// Refresh all metrics, deriving boot time from the given clock
+    ///
+    /// Every reader runs even if an earlier one fails, so metrics that could be
+    /// measured are kept; the first error is returned.
+    pub fn refresh_with_clock(&mut self, clock: &Clock) -> io::Result<()> {
+        self.refresh_with_progress(clock, &mut |_| {})
+    }
+
+    /// Like [`SystemMetrics::refresh_with_clock`], reporting each step before it runs
+    ///
+    /// `progress` is called once per step and a last time with
+    /// `completed == total` when collection is finished.
+    pub fn refresh_with_progress(&mut self, clock: &Clock, progress: &mut dyn FnMut(Progress)) -> io::Result<()> {
+        self.refresh_selected(&CollectOptions::default(), clock, progress)
+    }
+
+    /// Refresh only the metrics in `options.metrics`; the others become unknown
+    ///
+    /// Boot time is derived from uptime, so asking for it also reads uptime.
+    pub fn refresh_selected(
+        &mut self,
+        options: &CollectOptions,
+        clock: &Clock,
+        progress: &mut dyn FnMut(Progress),
+    ) -> io::Result<()> {
+        let set = options.metrics.with_dependencies();
+        let steps: Vec<&Step> = STEPS.iter().filter(|(metric, _, _)| set.contains(*metric)).collect();
+        let total = steps.len();
+        let mut results = Vec::with_capacity(total);
+
+        self.clear(set.complement());
+        for (completed, (_, step, read)) in steps.into_iter().enumerate() {
+            progress(Progress { step, completed, total });
+            results.push(read(self, options, clock));
+        }
+        progress(Progress { step: "done", completed: total, total });
+
+        results.into_iter().collect()
+    }
+
+    /// Forget the metrics in `set`
+    fn clear(&mut self, set: MetricSet) {
+        if set.contains(MetricSet::UPTIME) {
+            self.uptime_seconds = None;
+            self.idle_time = None;
+            self.awake_seconds = None;
+            self.suspended_seconds = None;
+        }
+        if set.contains(MetricSet::LOAD) {
+            self.load_avg = None;
+            self.tasks = None;
+        }
+        if set.contains(MetricSet::USERS) {
+            self.user_count = None;
+            self.session_count = None;
+            self.tty_count = None;
+            self.sessions = None;
+        }
+        if set.contains(MetricSet::CPU) {
+            self.cpu_times = None;
+            self.scheduler = None;
+        }
+        if set.contains(MetricSet::MEMORY) {
+            self.memory = None;
+        }
+        if set.contains(MetricSet::BOOT_TIME) {
+            self.boot_time = None;
+        }
+        if set.contains(MetricSet::BOOT_HISTORY) {
+            self.previous_boot = None;
+        }
+        if set.contains(MetricSet::HOST) {
+            self.host = None;
+        }
+        if set.contains(MetricSet::REBOOT) {
+            self.reboot = None;
+        }
+        if set.contains(MetricSet::CGROUP) {
+            self.cgroup = None;
+        }
+        if set.contains(MetricSet::PROCESSES) {
+            self.processes = None;
+        }
+    }
+}
+
+/// What a refresh collects and how
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectOptions {
+    /// Metrics to collect
+    pub metrics: MetricSet,
+    /// Which accounts count as users
+    pub users: UserFilter,
+    /// logind state directory tried before scanning /proc; `None` skips logind
+    pub logind_root: Option<PathBuf>,
+    /// Boot history file; `None` leaves the previous boot unknown
+    pub wtmp_path: Option<PathBuf>,
+    /// Clocks used for awake and suspended time
+    pub kernel_clock: KernelClock,
+    /// Root of the filesystem host identification, reboot markers and cgroups are read from
+    pub host_root: PathBuf,
+    /// Service or cgroup to report on; `None` leaves the cgroup unknown
+    pub cgroup: Option<CgroupTarget>,
+    /// Processes to report on; `None` leaves them unknown
+    pub processes: Option<ProcessTarget>,
+}
+
+impl Default for CollectOptions {
+    fn default() -> Self {
+        Self {
+            metrics: MetricSet::ALL,
+            users: UserFilter::default(),
+            logind_root: Some(PathBuf::from(logind::DEFAULT_ROOT)),
+            wtmp_path: Some(PathBuf::from(wtmp::DEFAULT_PATH)),
+            kernel_clock: KernelClock::System,
+            host_root: PathBuf::from("/"),
+            cgroup: None,
+            processes: None,
+        }
+    }
+}
+
+impl From<MetricSet> for CollectOptions {
+    fn from(metrics: MetricSet) -> Self {
+        Self { metrics, ..Self::default() }
+    }
+}
+
+/// Which metrics to collect
+///
+/// A small bit set: combine flags with `|`, e.g.
+/// `MetricSet::UPTIME | MetricSet::LOAD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MetricSet(u16);
+
+impl MetricSet {
+    /// Uptime and idle time from /proc/uptime, awake and suspended time
+    pub const UPTIME: MetricSet = MetricSet(1 << 0);
+    /// Load averages and task counts from /proc/loadavg
+    pub const LOAD: MetricSet = MetricSet(1 << 1);
+    /// User count and sessions; walks every process in /proc
+    pub const USERS: MetricSet = MetricSet(1 << 2);
+    /// CPU and scheduler counters from /proc/stat
+    pub const CPU: MetricSet = MetricSet(1 << 3);
+    /// Memory totals from /proc/meminfo
+    pub const MEMORY: MetricSet = MetricSet(1 << 4);
+    /// Boot time, derived from uptime and the clock
+    pub const BOOT_TIME: MetricSet = MetricSet(1 << 5);
+    /// Previous boot and how it ended, from wtmp
+    pub const BOOT_HISTORY: MetricSet = MetricSet(1 << 6);
+    /// Hostname, kernel, OS and virtualization
+    pub const HOST: MetricSet = MetricSet(1 << 7);
+    /// Pending reboot from package manager markers and installed kernels
+    pub const REBOOT: MetricSet = MetricSet(1 << 8);
+    /// Uptime and usage of the cgroup in [`CollectOptions::cgroup`]
+    pub const CGROUP: MetricSet = MetricSet(1 << 9);
+    /// Start time and CPU use of the processes in [`CollectOptions::processes`]
+    pub const PROCESSES: MetricSet = MetricSet(1 << 10);
+
+    /// No metrics
+    pub const EMPTY: MetricSet = MetricSet(0);
+    /// Every metric
+    pub const ALL: MetricSet = MetricSet((1 << 11) - 1);
+
+    /// Whether every metric in `other` is in this set
+    pub const fn contains(&self, other: MetricSet) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether the set is empty
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Metrics not in this set
+    pub const fn complement(&self) -> MetricSet {
+        MetricSet(!self.0 & MetricSet::ALL.0)
+    }
+
+    /// This set plus the metrics its members are computed from
+    pub const fn with_dependencies(&self) -> MetricSet {
+        if self.contains(MetricSet::BOOT_TIME) {
+            MetricSet(self.0 | MetricSet::UPTIME.0)
+        } else {
+            *self
+        }
+    }
+}
+
+impl Default for MetricSet {
+    fn default() -> Self {
+        MetricSet::ALL
+    }
+}
+
+impl std::ops::BitOr for MetricSet {
+    type Output = MetricSet;
+
+    fn bitor(self, other: MetricSet) -> MetricSet {
+        MetricSet(self.0 | other.0)
+    }
+}
+
+impl std::ops::BitOrAssign for MetricSet {
+    fn bitor_assign(&mut self, other: MetricSet) {
+        self.0 |= other.0;
+    }
+}
+
+/// How far a metrics collection has got
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Name of the step about to run, or `"done"`
+    pub step: &'static str,
+    /// Steps already finished
+    pub completed: usize,
+    /// Number of steps in the collection
+    pub total: usize,
+}
+
+/// A collection step: the metrics it fills, its name and its reader
+type Step = (MetricSet, &'static str, fn(&mut SystemMetrics, &CollectOptions, &Clock) -> io::Result<()>);
+
+/// Collection steps in the order they run; boot time comes after uptime
+const STEPS: [Step; 11] = [
+    (MetricSet::UPTIME, "uptime", |metrics, options, _| metrics.read_uptime(options)),
+    (MetricSet::LOAD, "load average", |metrics, _, _| metrics.read_loadavg()),
+    (MetricSet::USERS, "users", |metrics, options, _| metrics.read_users(options)),
+    (MetricSet::CPU, "cpu times", |metrics, _, _| metrics.read_cpu_times()),
+    (MetricSet::MEMORY, "memory", |metrics, _, _| metrics.read_meminfo()),
+    (MetricSet::BOOT_TIME, "boot time", |metrics, _, clock| metrics.calculate_boot_time(clock)),
+    (MetricSet::BOOT_HISTORY, "boot history", |metrics, options, _| metrics.read_boot_history(options)),
+    (MetricSet::HOST, "host info", |metrics, options, _| metrics.read_host(options)),
+    (MetricSet::REBOOT, "pending reboot", |metrics, options, _| metrics.read_reboot(options)),
+    (MetricSet::CGROUP, "cgroup", SystemMetrics::read_cgroup),
+    (MetricSet::PROCESSES, "processes", SystemMetrics::read_processes),
+];
+
+impl Progress {
+    /// Names of the collection steps in the order they run
+    pub fn steps() -> impl Iterator<Item = &'static str> {
+        STEPS.iter().map(|(_, step, _)| *step)
+    }
+
+    /// Whether collection has finished
+    pub fn is_done(&self) -> bool {
+        self.completed >= self.total
+    }
+}
+
+/// Terminal name for a `tty_nr` field of /proc/<pid>/stat
+fn tty_name(tty_nr: i32) -> String {
+    let major = (tty_nr >> 8) & 0xfff;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+
+    match major {
+        136..=143 => format!("pts/{}", (major - 136) * 256 + minor),
+        4 if minor < 64 => format!("tty{}", minor),
+        4 => format!("ttyS{}", minor - 64),
+        _ => format!("tty({}:{})", major, minor),
+    }
+}
+
+/// Where a `Runtime` gets its metrics from
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum MetricsSource {
+    /// Read live values from the /proc filesystem
+    #[default]
+    Procfs,
+    /// Use a fixed snapshot; refreshing leaves it unchanged
+    Fixed(Box<SystemMetrics>),
+}
+
+impl MetricsSource {
+    /// Update `metrics` from this source
+    ///
+    /// On error, metrics that could still be measured are kept.
+    pub fn refresh(&self, metrics: &mut SystemMetrics, clock: &Clock) -> io::Result<()> {
+        self.refresh_with_progress(metrics, &CollectOptions::default(), clock, &mut |_| {})
+    }
+
+    /// Update the metrics selected in `options`, reporting progress as each step starts
+    ///
+    /// A fixed snapshot is copied whole regardless of `options`.
+    pub fn refresh_with_progress(
+        &self,
+        metrics: &mut SystemMetrics,
+        options: &CollectOptions,
+        clock: &Clock,
+        progress: &mut dyn FnMut(Progress),
+    ) -> io::Result<()> {
+        match self {
+            MetricsSource::Procfs => metrics.refresh_selected(options, clock, progress),
+            MetricsSource::Fixed(snapshot) => {
+                *metrics = (**snapshot).clone();
+                progress(Progress { step: "done", completed: 1, total: 1 });
+                Ok(())
+            }
+        }
     }
 }
@@ -0,0 +1,206 @@
+//! Small table layout engine for the Interactive dashboard
+//!
+//! Widths are measured on what the terminal actually shows: ANSI escape
+//! sequences take no space and wide characters (CJK, most emoji) take two
+//! columns. The table grows to fit its widest row and falls back to a
+//! single-column layout when the terminal is too narrow for the box.
+
+use std::io::IsTerminal;
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::glyphs::Glyphs;
+
+/// Minimum inner width of the boxed layout, matching the classic dashboard
+const MIN_INNER_WIDTH: usize = 55;
+
+/// Columns the terminal would use to display `text`, ignoring escape codes
+pub fn visible_width(text: &str) -> usize {
+    strip_ansi(text)
+        .chars()
+        .map(|c| c.width().unwrap_or(0))
+        .sum()
+}
+
+/// Remove ANSI CSI (`ESC [ ... final`) and OSC (`ESC ] ... BEL/ST`) sequences
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            plain.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                // Parameters and intermediates, then one final byte in @..~
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\u{7}' {
+                        break;
+                    }
+                    if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    plain
+}
+
+/// Width of the terminal attached to stdout
+///
+/// Asks the tty with `TIOCGWINSZ` first and falls back to `$COLUMNS`.
+/// Returns `None` when neither is available, e.g. when output is piped.
+pub fn terminal_width() -> Option<usize> {
+    if let Some((columns, _)) = terminal_size() {
+        return Some(columns);
+    }
+
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse().ok())
+        .filter(|columns| *columns > 0)
+}
+
+/// Columns and rows of the terminal attached to stdout, via `TIOCGWINSZ`
+pub fn terminal_size() -> Option<(usize, usize)> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    let mut size = libc::winsize { ws_row: 0, ws_col: 0, ws_xpixel: 0, ws_ypixel: 0 };
+    // SAFETY: TIOCGWINSZ only writes into the winsize struct we pass
+    let result = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) };
+    if result == 0 && size.ws_col > 0 && size.ws_row > 0 {
+        Some((size.ws_col as usize, size.ws_row as usize))
+    } else {
+        None
+    }
+}
+
+/// A titled two-column table of labels and values
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    title: String,
+    rows: Vec<(String, String)>,
+    /// Row counts after which a rule is drawn
+    separators: Vec<usize>,
+    glyphs: Glyphs,
+}
+
+impl Table {
+    /// Creates an empty table; the title may contain escape codes
+    pub fn new(title: impl Into<String>) -> Self {
+        Self { title: title.into(), rows: Vec::new(), separators: Vec::new(), glyphs: Glyphs::Ascii }
+    }
+
+    /// Characters used for the borders; ASCII by default
+    pub fn glyphs(mut self, glyphs: Glyphs) -> Self {
+        self.glyphs = glyphs;
+        self
+    }
+
+    /// Append a row; the value may contain escape codes
+    pub fn row(mut self, label: impl Into<String>, value: impl Into<String>) -> Self {
+        self.rows.push((label.into(), value.into()));
+        self
+    }
+
+    /// Draw a rule between the rows added so far and the next ones
+    pub fn separator(mut self) -> Self {
+        if !self.rows.is_empty() && self.separators.last() != Some(&self.rows.len()) {
+            self.separators.push(self.rows.len());
+        }
+        self
+    }
+
+    /// Width of the label column, leaving room after the longest label
+    fn label_width(&self) -> usize {
+        self.rows.iter().map(|(label, _)| visible_width(label)).max().unwrap_or(0) + 3
+    }
+
+    /// Inner width (between the side borders) the boxed layout needs
+    fn required_inner_width(&self) -> usize {
+        let label_width = self.label_width();
+        let widest_row = self.rows
+            .iter()
+            .map(|(_, value)| label_width + 2 + visible_width(value))
+            .max()
+            .unwrap_or(0);
+
+        (widest_row + 2).max(visible_width(&self.title) + 2)
+    }
+
+    /// Render the table for a terminal `max_width` columns wide
+    ///
+    /// `None` means the width is unknown and the box is never narrowed.
+    /// `paint_border` is applied to every border segment.
+    pub fn render(&self, max_width: Option<usize>, paint_border: impl Fn(&str) -> String) -> String {
+        let required = self.required_inner_width();
+        let available = max_width.map_or(usize::MAX, |width| width.saturating_sub(2));
+
+        if required > available {
+            return self.render_compact(&paint_border);
+        }
+
+        let inner = required.max(MIN_INNER_WIDTH.min(available));
+        let label_width = self.label_width();
+        let border = self.glyphs.border();
+        let line = border.horizontal.to_string().repeat(inner);
+        let rule = |left: char, right: char| format!("{}\n", paint_border(&format!("{}{}{}", left, line, right)));
+        let side = paint_border(&border.vertical.to_string());
+
+        let mut out = String::from("\n");
+        out.push_str(&rule(border.top_left, border.top_right));
+
+        let title_space = inner - visible_width(&self.title);
+        let left = title_space / 2;
+        out.push_str(&format!("{}{}{}{}{}\n",
+            side, " ".repeat(left), self.title, " ".repeat(title_space - left), side));
+        out.push_str(&rule(border.left_tee, border.right_tee));
+
+        for (index, (label, value)) in self.rows.iter().enumerate() {
+            if index > 0 && self.separators.contains(&index) {
+                out.push_str(&rule(border.left_tee, border.right_tee));
+            }
+            let used = 1 + label_width + 2 + visible_width(value);
+            out.push_str(&format!("{} {}{}: {}{}{}\n",
+                side,
+                label,
+                " ".repeat(label_width - visible_width(label)),
+                value,
+                " ".repeat(inner - used),
+                side));
+        }
+
+        out.push_str(&rule(border.bottom_left, border.bottom_right));
+        out
+    }
+
+    /// Single-column layout: each label on its own line, value indented below
+    fn render_compact(&self, paint_border: &impl Fn(&str) -> String) -> String {
+        let marker = paint_border(&self.glyphs.border().horizontal.to_string().repeat(2));
+        let mut out = format!("\n{} {} {}\n", marker, self.title, marker);
+
+        for (index, (label, value)) in self.rows.iter().enumerate() {
+            if index > 0 && self.separators.contains(&index) {
+                out.push('\n');
+            }
+            out.push_str(&format!("{}\n  {}\n", label, value));
+        }
+
+        out
+    }
+}
@@ -0,0 +1,531 @@
+//! Color themes for the Interactive dashboard
+//!
+//! A [`Theme`] maps each [`Role`] (border, load levels, uptime units, ...) to a
+//! [`Style`]. Colors are written as named ANSI colors, 256-color indexes or
+//! `#rrggbb`, and a [`Painter`] downsamples them to what the terminal supports.
+//!
+//! User themes are plain `role = "style"` files:
+//!
+//! ```text
+//! # ~/.config/runtime/themes/solarized.theme
+//! inherits = "dark"
+//! border = "#268bd2 bold"
+//! load_high = "208 bold"
+//! muted = "dim"
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::config::config_dir;
+
+/// How many colors the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    /// The 16 standard ANSI colors
+    Ansi16,
+    /// The xterm 256-color palette
+    Ansi256,
+    /// 24-bit RGB
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Guess the terminal's capability from `COLORTERM` and `TERM`
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        let term = std::env::var("TERM").unwrap_or_default();
+
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+}
+
+/// A foreground color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    /// One of the 16 standard colors (8-15 are the bright variants)
+    Ansi(u8),
+    /// An index into the 256-color palette
+    Indexed(u8),
+    /// A 24-bit color
+    Rgb(u8, u8, u8),
+}
+
+/// Approximate RGB values of the 16 standard colors (xterm defaults)
+const ANSI_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+    (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+    (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+];
+
+/// Names accepted for the 16 standard colors, in palette order
+const ANSI_NAMES: [&str; 16] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    "bright-black", "bright-red", "bright-green", "bright-yellow",
+    "bright-blue", "bright-magenta", "bright-cyan", "bright-white",
+];
+
+impl Color {
+    /// RGB value of this color
+    fn rgb(&self) -> (u8, u8, u8) {
+        match *self {
+            Color::Ansi(index) => ANSI_RGB[index as usize % 16],
+            Color::Indexed(index) if index < 16 => ANSI_RGB[index as usize],
+            Color::Indexed(index) if index >= 232 => {
+                let level = 8 + (index - 232) * 10;
+                (level, level, level)
+            }
+            Color::Indexed(index) => {
+                let cube = index - 16;
+                let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+                (level(cube / 36), level((cube / 6) % 6), level(cube % 6))
+            }
+            Color::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+
+    /// SGR parameters selecting this color as foreground at the given depth
+    fn sgr(&self, depth: ColorDepth) -> String {
+        match (*self, depth) {
+            (Color::Ansi(index), _) => ansi16_sgr(index),
+            (Color::Indexed(index), ColorDepth::Ansi256 | ColorDepth::TrueColor) => format!("38;5;{}", index),
+            (Color::Rgb(r, g, b), ColorDepth::TrueColor) => format!("38;2;{};{};{}", r, g, b),
+            (Color::Rgb(r, g, b), ColorDepth::Ansi256) => format!("38;5;{}", nearest_256(r, g, b)),
+            (color, ColorDepth::Ansi16) => {
+                let (r, g, b) = color.rgb();
+                ansi16_sgr(nearest_16(r, g, b))
+            }
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(hex) = s.strip_prefix('#') {
+            let channel = |range: std::ops::Range<usize>| {
+                hex.get(range).and_then(|digits| u8::from_str_radix(digits, 16).ok())
+            };
+            if hex.len() == 6 {
+                if let (Some(r), Some(g), Some(b)) = (channel(0..2), channel(2..4), channel(4..6)) {
+                    return Ok(Color::Rgb(r, g, b));
+                }
+            }
+            return Err(format!("invalid hex color '{}'", s));
+        }
+        if let Ok(index) = s.parse::<u8>() {
+            return Ok(Color::Indexed(index));
+        }
+
+        let name = s.replace('_', "-");
+        ANSI_NAMES
+            .iter()
+            .position(|candidate| *candidate == name)
+            .map(|index| Color::Ansi(index as u8))
+            .ok_or_else(|| format!("unknown color '{}'", s))
+    }
+}
+
+fn ansi16_sgr(index: u8) -> String {
+    let index = index % 16;
+    if index < 8 {
+        format!("{}", 30 + index)
+    } else {
+        format!("{}", 90 + index - 8)
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+fn nearest_16(r: u8, g: u8, b: u8) -> u8 {
+    (0..16u8)
+        .min_by_key(|index| distance(ANSI_RGB[*index as usize], (r, g, b)))
+        .unwrap_or(7)
+}
+
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    // Closest entry of the 6x6x6 cube or the 24-step gray ramp
+    (16..=255u8)
+        .min_by_key(|index| distance(Color::Indexed(*index).rgb(), (r, g, b)))
+        .unwrap_or(15)
+}
+
+/// Foreground color and attributes for one role
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+}
+
+impl Style {
+    const fn fg(color: Color) -> Self {
+        Self { fg: Some(color), bold: false, dim: false }
+    }
+
+    const fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    const fn plain() -> Self {
+        Self { fg: None, bold: false, dim: false }
+    }
+
+    const fn dimmed() -> Self {
+        Self { fg: None, bold: false, dim: true }
+    }
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    /// Parse whitespace-separated words: at most one color plus `bold`/`dim`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut style = Style::default();
+        for word in s.split_whitespace() {
+            match word {
+                "bold" => style.bold = true,
+                "dim" => style.dim = true,
+                "plain" | "none" => {}
+                color => style.fg = Some(color.parse()?),
+            }
+        }
+        Ok(style)
+    }
+}
+
+/// Parts of the dashboard that can be styled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Border,
+    Title,
+    Value,
+    Count,
+    Muted,
+    Weeks,
+    Days,
+    Hours,
+    Minutes,
+    Seconds,
+    LoadLow,
+    LoadMedium,
+    LoadHigh,
+    LoadCritical,
+    Container,
+    Native,
+}
+
+impl Role {
+    /// Every role, in the order used by [`Theme`]'s style table
+    pub const ALL: [Role; 16] = [
+        Role::Border, Role::Title, Role::Value, Role::Count, Role::Muted,
+        Role::Weeks, Role::Days, Role::Hours, Role::Minutes, Role::Seconds,
+        Role::LoadLow, Role::LoadMedium, Role::LoadHigh, Role::LoadCritical,
+        Role::Container, Role::Native,
+    ];
+
+    /// Key used for this role in theme files
+    pub fn key(&self) -> &'static str {
+        match self {
+            Role::Border => "border",
+            Role::Title => "title",
+            Role::Value => "value",
+            Role::Count => "count",
+            Role::Muted => "muted",
+            Role::Weeks => "weeks",
+            Role::Days => "days",
+            Role::Hours => "hours",
+            Role::Minutes => "minutes",
+            Role::Seconds => "seconds",
+            Role::LoadLow => "load_low",
+            Role::LoadMedium => "load_medium",
+            Role::LoadHigh => "load_high",
+            Role::LoadCritical => "load_critical",
+            Role::Container => "container",
+            Role::Native => "native",
+        }
+    }
+
+    fn index(&self) -> usize {
+        Role::ALL.iter().position(|role| role == self).unwrap_or(0)
+    }
+}
+
+/// Names of the built-in themes
+pub const BUILTIN_THEMES: [&str; 4] = ["dark", "light", "high-contrast", "colorblind"];
+
+/// A named set of styles, one per [`Role`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    name: String,
+    styles: [Style; Role::ALL.len()],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    fn from_styles(name: &str, styles: [Style; Role::ALL.len()]) -> Self {
+        Self { name: name.to_string(), styles }
+    }
+
+    /// The classic palette, tuned for dark backgrounds
+    pub fn dark() -> Self {
+        Self::from_styles("dark", [
+            Style::fg(Color::Ansi(12)).bold(),  // border
+            Style::fg(Color::Ansi(11)),         // title
+            Style::fg(Color::Ansi(15)).bold(),  // value
+            Style::fg(Color::Ansi(14)).bold(),  // count
+            Style::dimmed(),                    // muted
+            Style::fg(Color::Ansi(12)).bold(),  // weeks
+            Style::fg(Color::Ansi(14)).bold(),  // days
+            Style::fg(Color::Ansi(10)).bold(),  // hours
+            Style::fg(Color::Ansi(11)).bold(),  // minutes
+            Style::fg(Color::Ansi(13)).bold(),  // seconds
+            Style::fg(Color::Ansi(10)).bold(),  // load_low
+            Style::fg(Color::Ansi(11)).bold(),  // load_medium
+            Style::fg(Color::Ansi(9)).bold(),   // load_high
+            Style::fg(Color::Ansi(1)).bold(),   // load_critical
+            Style::fg(Color::Ansi(14)).bold(),  // container
+            Style::fg(Color::Ansi(10)).bold(),  // native
+        ])
+    }
+
+    /// Darker colors that stay readable on light backgrounds
+    pub fn light() -> Self {
+        Self::from_styles("light", [
+            Style::fg(Color::Rgb(0, 70, 160)).bold(),
+            Style::fg(Color::Rgb(170, 90, 0)),
+            Style::fg(Color::Ansi(0)).bold(),
+            Style::fg(Color::Rgb(0, 110, 130)).bold(),
+            Style::fg(Color::Rgb(110, 110, 110)),
+            Style::fg(Color::Rgb(0, 70, 160)).bold(),
+            Style::fg(Color::Rgb(0, 110, 130)).bold(),
+            Style::fg(Color::Rgb(0, 120, 40)).bold(),
+            Style::fg(Color::Rgb(150, 90, 0)).bold(),
+            Style::fg(Color::Rgb(140, 0, 140)).bold(),
+            Style::fg(Color::Rgb(0, 120, 40)).bold(),
+            Style::fg(Color::Rgb(150, 90, 0)).bold(),
+            Style::fg(Color::Rgb(190, 30, 30)).bold(),
+            Style::fg(Color::Rgb(130, 0, 0)).bold(),
+            Style::fg(Color::Rgb(0, 110, 130)).bold(),
+            Style::fg(Color::Rgb(0, 120, 40)).bold(),
+        ])
+    }
+
+    /// Bold text in the terminal's own foreground, color only for load levels
+    pub fn high_contrast() -> Self {
+        let strong = Style::plain().bold();
+        Self::from_styles("high-contrast", [
+            strong, strong, strong, strong, Style::plain(),
+            strong, strong, strong, strong, strong,
+            Style::fg(Color::Ansi(10)).bold(),
+            Style::fg(Color::Ansi(11)).bold(),
+            Style::fg(Color::Ansi(9)).bold(),
+            Style::fg(Color::Ansi(13)).bold(),
+            strong, strong,
+        ])
+    }
+
+    /// Okabe-Ito palette, distinguishable with common color vision deficiencies
+    pub fn colorblind() -> Self {
+        let blue = Color::Rgb(0, 114, 178);
+        let sky = Color::Rgb(86, 180, 233);
+        let green = Color::Rgb(0, 158, 115);
+        let yellow = Color::Rgb(240, 228, 66);
+        let orange = Color::Rgb(230, 159, 0);
+        let vermillion = Color::Rgb(213, 94, 0);
+        let purple = Color::Rgb(204, 121, 167);
+
+        Self::from_styles("colorblind", [
+            Style::fg(sky).bold(),
+            Style::fg(orange),
+            Style::plain().bold(),
+            Style::fg(sky).bold(),
+            Style::dimmed(),
+            Style::fg(blue).bold(),
+            Style::fg(sky).bold(),
+            Style::fg(green).bold(),
+            Style::fg(yellow).bold(),
+            Style::fg(purple).bold(),
+            Style::fg(sky).bold(),
+            Style::fg(yellow).bold(),
+            Style::fg(vermillion).bold(),
+            Style::fg(purple).bold(),
+            Style::fg(sky).bold(),
+            Style::fg(green).bold(),
+        ])
+    }
+
+    /// A built-in theme by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" | "default" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "colorblind" | "colorblind-safe" => Some(Self::colorblind()),
+            _ => None,
+        }
+    }
+
+    /// Resolve `--theme`: a built-in name, a file path, or a file in the
+    /// user theme directory (`~/.config/runtime/themes/<name>.theme`)
+    pub fn load(name_or_path: &str) -> Result<Self, String> {
+        if let Some(theme) = Self::builtin(name_or_path) {
+            return Ok(theme);
+        }
+
+        let path = Path::new(name_or_path);
+        if path.is_file() {
+            return Self::from_file(path);
+        }
+        if let Some(user_theme) = config_dir().map(|dir| dir.join("themes").join(format!("{}.theme", name_or_path))) {
+            if user_theme.is_file() {
+                return Self::from_file(&user_theme);
+            }
+        }
+
+        Err(format!("unknown theme '{}' (built-in themes: {})", name_or_path, BUILTIN_THEMES.join(", ")))
+    }
+
+    /// Load a user theme file
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("cannot read theme {}: {}", path.display(), e))?;
+        let name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("custom");
+
+        Self::parse(name, &content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Parse theme file contents; roles not mentioned keep the inherited style
+    pub fn parse(name: &str, content: &str) -> Result<Self, String> {
+        let mut theme = Self::dark();
+        theme.name = name.to_string();
+        let mut overridden = false;
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `role = \"style\"`", number + 1))?;
+            let key = key.trim();
+            let value = unquote(value.trim());
+
+            if key == "inherits" {
+                if overridden {
+                    return Err(format!("line {}: `inherits` must come before any role", number + 1));
+                }
+                let base = Self::builtin(value)
+                    .ok_or_else(|| format!("line {}: unknown built-in theme '{}'", number + 1, value))?;
+                theme.styles = base.styles;
+                continue;
+            }
+
+            let role = Role::ALL
+                .iter()
+                .find(|role| role.key() == key)
+                .ok_or_else(|| format!("line {}: unknown role '{}'", number + 1, key))?;
+            theme.styles[role.index()] = value
+                .parse()
+                .map_err(|e| format!("line {}: {}", number + 1, e))?;
+            overridden = true;
+        }
+
+        Ok(theme)
+    }
+
+    /// The theme's name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Style for a role
+    pub fn style(&self, role: Role) -> Style {
+        self.styles[role.index()]
+    }
+}
+
+/// The text between quotes, dropping any trailing comment; bare values as-is
+///
+/// Comments after a bare value are not supported since `#rrggbb` looks alike.
+fn unquote(value: &str) -> &str {
+    match value.strip_prefix('"') {
+        Some(rest) => rest.split('"').next().unwrap_or(""),
+        None => value,
+    }
+}
+
+/// Applies a theme's styles to text
+#[derive(Debug, Clone)]
+pub struct Painter<'a> {
+    theme: &'a Theme,
+    depth: ColorDepth,
+    enabled: bool,
+}
+
+impl<'a> Painter<'a> {
+    /// Creates a painter; with `enabled == false` text is returned unchanged
+    pub fn new(theme: &'a Theme, depth: ColorDepth, enabled: bool) -> Self {
+        Self { theme, depth, enabled }
+    }
+
+    /// Wrap `text` in the escape codes for `role`
+    pub fn paint(&self, role: Role, text: impl fmt::Display) -> String {
+        let style = self.theme.style(role);
+        let mut codes = Vec::new();
+
+        if style.bold {
+            codes.push("1".to_string());
+        }
+        if style.dim {
+            codes.push("2".to_string());
+        }
+        if let Some(color) = style.fg {
+            codes.push(color.sgr(self.depth));
+        }
+
+        if !self.enabled || codes.is_empty() {
+            return text.to_string();
+        }
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    }
+}
+
+/// Whether colors should be used when the policy is "auto"
+///
+/// Honours `NO_COLOR`, `CLICOLOR_FORCE` and `CLICOLOR=0`, otherwise colors
+/// only when stdout is a terminal.
+pub fn auto_color_enabled() -> bool {
+    if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+    if std::env::var("CLICOLOR_FORCE").is_ok_and(|value| value != "0") {
+        return true;
+    }
+    if std::env::var("CLICOLOR").is_ok_and(|value| value == "0") {
+        return false;
+    }
+    std::io::stdout().is_terminal()
+}
@@ -0,0 +1,88 @@
+//! Time zone and format settings applied to every printed timestamp
+//!
+//! Boot time and current time are kept as UTC instants internally and only
+//! converted when rendered, so hosts in different regions can be made to line
+//! up with `--utc` or `--tz`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::format::StrftimeItems;
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+
+/// Time zone used to display timestamps
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum TimeZoneChoice {
+    /// The system's local time zone (honours `TZ`)
+    #[default]
+    Local,
+    /// Coordinated Universal Time
+    Utc,
+    /// A named IANA time zone such as `Europe/Berlin`
+    Named(chrono_tz::Tz),
+}
+
+impl FromStr for TimeZoneChoice {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "local" | "Local" => Ok(TimeZoneChoice::Local),
+            "utc" | "UTC" => Ok(TimeZoneChoice::Utc),
+            _ => name.parse::<chrono_tz::Tz>()
+                .map(TimeZoneChoice::Named)
+                .map_err(|_| format!("unknown time zone '{}'", name)),
+        }
+    }
+}
+
+/// How timestamps are written
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum TimeStyle {
+    /// Each field's usual format, e.g. `%Y-%m-%d %H:%M:%S` for boot time
+    #[default]
+    Default,
+    /// ISO 8601 / RFC 3339, e.g. `2024-03-01T12:34:56+01:00`
+    Rfc3339,
+    /// A strftime pattern used for every timestamp
+    Custom(String),
+}
+
+impl TimeStyle {
+    /// Validate a strftime pattern and wrap it as a custom style
+    pub fn custom(pattern: &str) -> Result<Self, String> {
+        StrftimeItems::new(pattern)
+            .parse()
+            .map(|_| TimeStyle::Custom(pattern.to_string()))
+            .map_err(|_| format!("invalid time format '{}'", pattern))
+    }
+}
+
+/// Time zone and style for every timestamp `Runtime` prints
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TimeSettings {
+    pub zone: TimeZoneChoice,
+    pub style: TimeStyle,
+}
+
+impl TimeSettings {
+    /// Format an instant, using `default_format` unless a style overrides it
+    pub fn format(&self, instant: DateTime<Utc>, default_format: &str) -> String {
+        match &self.zone {
+            TimeZoneChoice::Local => self.format_in(instant.with_timezone(&Local), default_format),
+            TimeZoneChoice::Utc => self.format_in(instant, default_format),
+            TimeZoneChoice::Named(tz) => self.format_in(instant.with_timezone(tz), default_format),
+        }
+    }
+
+    fn format_in<Tz: TimeZone>(&self, instant: DateTime<Tz>, default_format: &str) -> String
+    where
+        Tz::Offset: fmt::Display,
+    {
+        match &self.style {
+            TimeStyle::Default => instant.format(default_format).to_string(),
+            TimeStyle::Rfc3339 => instant.to_rfc3339_opts(SecondsFormat::Secs, true),
+            TimeStyle::Custom(pattern) => instant.format(pattern).to_string(),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Terminal driver for `runtime top`
+//!
+//! Puts the terminal in raw mode on the alternate screen, refreshes metrics on
+//! a timer and paints whatever `runtime::tui::render` returns.
+
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
+
+use runtime::table::terminal_size;
+use runtime::tui::{self, History, ViewState};
+use runtime::system_metrics::MetricSet;
+use runtime::{RuntimeArgs, RuntimeBuilder};
+
+/// Samples kept for the charts; enough for a wide terminal
+const HISTORY_CAPACITY: usize = 512;
+
+/// Restores the terminal settings when dropped, even on early return
+struct RawTerminal {
+    original: libc::termios,
+}
+
+impl RawTerminal {
+    fn enter() -> io::Result<Self> {
+        // SAFETY: tcgetattr/tcsetattr only read and write the termios structs we own
+        let original = unsafe {
+            let mut original = std::mem::zeroed::<libc::termios>();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            // Without ISIG, Ctrl-C arrives as 0x03 and quits through `Drop`
+            let mut raw = original;
+            raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+            raw.c_cc[libc::VMIN] = 0;
+            raw.c_cc[libc::VTIME] = 0;
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            original
+        };
+
+        // Alternate screen, hidden cursor
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush()?;
+
+        Ok(Self { original })
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        // SAFETY: restores the settings captured in `enter`
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+/// Wait up to `timeout` for a key press
+fn read_key(timeout: Duration) -> io::Result<Option<u8>> {
+    let mut poll_fd = libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 };
+    let millis = timeout.as_millis().min(i32::MAX as u128) as i32;
+
+    // SAFETY: polls a single descriptor we pass by reference
+    let ready = unsafe { libc::poll(&mut poll_fd, 1, millis) };
+    if ready < 0 {
+        let error = io::Error::last_os_error();
+        // A resize (SIGWINCH) interrupts poll; just redraw
+        return if error.kind() == io::ErrorKind::Interrupted { Ok(None) } else { Err(error) };
+    }
+    if ready == 0 {
+        return Ok(None);
+    }
+
+    let mut key = [0u8; 1];
+    match io::stdin().read(&mut key)? {
+        0 => Ok(None),
+        _ => Ok(Some(key[0])),
+    }
+}
+
+/// Run the full-screen dashboard until the user quits
+pub fn run(args: RuntimeArgs, interval: Duration, load_threshold: Option<f64>) -> io::Result<()> {
+    // Only what the panels draw; the rest would be re-read on every refresh
+    let metrics = MetricSet::UPTIME | MetricSet::LOAD | MetricSet::USERS | MetricSet::CPU | MetricSet::MEMORY;
+    let mut runtime = RuntimeBuilder::from(args).metric_set(metrics).build();
+    let mut history = History::new(HISTORY_CAPACITY);
+    history.push(runtime.metrics());
+
+    let mut view = ViewState::new(interval);
+    if let Some(load_threshold) = load_threshold {
+        view.load_threshold = load_threshold;
+    }
+    let _terminal = RawTerminal::enter()?;
+    let mut next_refresh = Instant::now() + view.interval;
+
+    loop {
+        let (width, height) = terminal_size().unwrap_or((80, 24));
+        let screen = tui::render(&history, runtime.metrics(), &view, runtime.options(), width, height);
+
+        let mut out = io::stdout().lock();
+        write!(out, "\x1b[H{}", screen.lines().join("\r\n"))?;
+        out.flush()?;
+        drop(out);
+
+        let timeout = next_refresh.saturating_duration_since(Instant::now());
+        if let Some(key) = read_key(timeout)? {
+            let previous = view.interval;
+            if !view.handle_key(key) {
+                return Ok(());
+            }
+            if view.interval != previous {
+                next_refresh = Instant::now() + view.interval;
+            }
+        }
+
+        if Instant::now() >= next_refresh {
+            if !view.paused {
+                runtime.refresh();
+                history.push(runtime.metrics());
+            }
+            next_refresh = Instant::now() + view.interval;
+        }
+    }
+}
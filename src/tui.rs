@@ -0,0 +1,432 @@
+//! Full-screen `runtime top` dashboard
+//!
+//! Rendering is a pure function from a [`History`] of samples and the latest
+//! metrics to a [`Screen`] of lines, so it can be tested without a terminal.
+//! The binary only reads keys, refreshes metrics and paints the returned lines.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::duration::DurationStyle;
+use crate::load_trend::{self, Forecast, LoadTrend};
+use crate::render::RenderOptions;
+use crate::system_metrics::{CpuTimes, SystemMetrics};
+
+/// Shortest refresh interval reachable with the `+` key
+pub const MIN_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Longest refresh interval reachable with the `-` key
+pub const MAX_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Sparkline levels from empty to full
+const SPARK_LEVELS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One refresh worth of values for the charts
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Sample {
+    /// Uptime when the sample was taken, the time axis of the load forecast
+    pub uptime: Option<f64>,
+    pub load: Option<(f64, f64, f64)>,
+    pub cpu_percent: Option<f64>,
+    pub memory_percent: Option<f64>,
+}
+
+/// Ring buffer of the most recent samples
+#[derive(Debug, Clone)]
+pub struct History {
+    samples: VecDeque<Sample>,
+    capacity: usize,
+    last_cpu: Option<CpuTimes>,
+}
+
+impl History {
+    /// Creates an empty history keeping at most `capacity` samples
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            last_cpu: None,
+        }
+    }
+
+    /// Record a sample derived from freshly collected metrics
+    ///
+    /// CPU usage needs two readings, so the first sample has none.
+    pub fn push(&mut self, metrics: &SystemMetrics) {
+        let cpu_times = metrics.cpu_times();
+        let cpu_percent = match (cpu_times, self.last_cpu) {
+            (Some(now), Some(earlier)) => now.usage_since(&earlier),
+            _ => None,
+        };
+        self.last_cpu = cpu_times;
+
+        self.push_sample(Sample {
+            uptime: metrics.uptime_seconds(),
+            load: metrics.load_averages(),
+            cpu_percent,
+            memory_percent: metrics.memory().map(|memory| memory.used_percent()),
+        });
+    }
+
+    /// Record an already computed sample
+    pub fn push_sample(&mut self, sample: Sample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Samples from oldest to newest
+    pub fn samples(&self) -> impl Iterator<Item = &Sample> {
+        self.samples.iter()
+    }
+
+    /// The newest sample
+    pub fn latest(&self) -> Option<&Sample> {
+        self.samples.back()
+    }
+
+    /// Number of samples recorded
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Whether no samples were recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Straight-line forecast of the 1-minute load towards `threshold`
+    ///
+    /// `None` until enough samples with both uptime and load were recorded.
+    pub fn load_forecast(&self, threshold: f64) -> Option<Forecast> {
+        let points: Vec<(f64, f64)> = self.samples
+            .iter()
+            .filter_map(|sample| Some((sample.uptime?, sample.load?.0)))
+            .collect();
+        Forecast::fit(&points, threshold)
+    }
+}
+
+/// Panels that can be switched with Tab or the number keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Panel {
+    /// Load sparklines, gauges and sessions together
+    #[default]
+    Overview,
+    /// Large load average charts
+    Load,
+    /// Full session list
+    Sessions,
+}
+
+impl Panel {
+    const ALL: [Panel; 3] = [Panel::Overview, Panel::Load, Panel::Sessions];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Panel::Overview => "Overview",
+            Panel::Load => "Load",
+            Panel::Sessions => "Sessions",
+        }
+    }
+
+    fn next(&self) -> Panel {
+        match self {
+            Panel::Overview => Panel::Load,
+            Panel::Load => Panel::Sessions,
+            Panel::Sessions => Panel::Overview,
+        }
+    }
+}
+
+/// What the user changed with the keyboard
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewState {
+    pub panel: Panel,
+    pub paused: bool,
+    pub interval: Duration,
+    /// Load the forecast counts towards
+    pub load_threshold: f64,
+}
+
+impl ViewState {
+    /// Creates the initial view refreshing every `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            panel: Panel::default(),
+            paused: false,
+            interval: interval.clamp(MIN_INTERVAL, MAX_INTERVAL),
+            load_threshold: load_trend::default_threshold(),
+        }
+    }
+
+    /// Apply a key press; returns `false` when the user asked to quit
+    pub fn handle_key(&mut self, key: u8) -> bool {
+        match key {
+            b'q' | b'Q' | 0x03 => return false,
+            b' ' | b'p' | b'P' => self.paused = !self.paused,
+            b'+' | b'=' => self.interval = (self.interval / 2).max(MIN_INTERVAL),
+            b'-' | b'_' => self.interval = (self.interval * 2).min(MAX_INTERVAL),
+            b'\t' => self.panel = self.panel.next(),
+            b'1' => self.panel = Panel::Overview,
+            b'2' => self.panel = Panel::Load,
+            b'3' => self.panel = Panel::Sessions,
+            _ => {}
+        }
+        true
+    }
+}
+
+/// A full screen of plain-text lines, each exactly `width` columns wide
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screen {
+    lines: Vec<String>,
+}
+
+impl Screen {
+    /// The lines from top to bottom
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+}
+
+/// Render the dashboard for a terminal of `width` x `height` cells
+pub fn render(
+    history: &History,
+    metrics: &SystemMetrics,
+    view: &ViewState,
+    options: &RenderOptions,
+    width: usize,
+    height: usize,
+) -> Screen {
+    let mut lines = Vec::new();
+
+    let uptime = options.uptime_clock.seconds(metrics).map_or_else(
+        || "unknown".to_string(),
+        |uptime| options.duration.resolve(DurationStyle::Compact).format(uptime),
+    );
+    let now = options.time.format(options.clock.now(), "%H:%M:%S");
+    lines.push(format!("runtime top - {}  up {}", now, uptime));
+
+    let state = if view.paused { "paused" } else { "running" };
+    lines.push(format!("[{}] refresh every {}", state, format_interval(view.interval)));
+
+    let tabs: Vec<String> = Panel::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, panel)| {
+            if *panel == view.panel {
+                format!("[{} {}]", index + 1, panel.title())
+            } else {
+                format!(" {} {} ", index + 1, panel.title())
+            }
+        })
+        .collect();
+    lines.push(tabs.join(" "));
+    lines.push(String::new());
+
+    // Leave the last line for the key help
+    let body_height = height.saturating_sub(lines.len() + 1);
+    let body = match view.panel {
+        Panel::Overview => overview_panel(history, metrics, view, options, width, body_height),
+        Panel::Load => load_panel(history, view, options, width, body_height),
+        Panel::Sessions => sessions_panel(metrics, body_height),
+    };
+    lines.extend(body.into_iter().take(body_height));
+
+    lines.truncate(height.saturating_sub(1));
+    while lines.len() + 1 < height {
+        lines.push(String::new());
+    }
+    if height > 0 {
+        lines.push("q quit  space pause  +/- refresh rate  tab/1-3 panels".to_string());
+    }
+
+    Screen {
+        lines: lines.iter().map(|line| fit(line, width)).collect(),
+    }
+}
+
+fn overview_panel(
+    history: &History,
+    metrics: &SystemMetrics,
+    view: &ViewState,
+    options: &RenderOptions,
+    width: usize,
+    height: usize,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    let latest = history.latest().copied().unwrap_or_default();
+    let scale = load_scale(history);
+
+    for (label, pick) in load_series() {
+        let values: Vec<f64> = history.samples().filter_map(|sample| sample.load.map(pick)).collect();
+        let current = latest.load.map(pick).map_or_else(|| "   n/a".to_string(), |load| format!("{:6.2}", load));
+        let spark_width = width.saturating_sub(16);
+        lines.push(format!("Load {} {}  {}", label, current, sparkline(&values, spark_width, scale)));
+    }
+    lines.push(trend_line(history, view, options));
+    lines.push(String::new());
+
+    let gauge_width = width.saturating_sub(16).min(50);
+    lines.push(format!("CPU  {}", gauge(latest.cpu_percent, gauge_width)));
+    lines.push(format!("Mem  {}", gauge(latest.memory_percent, gauge_width)));
+    lines.push(String::new());
+
+    let remaining = height.saturating_sub(lines.len());
+    lines.extend(sessions_panel(metrics, remaining));
+    lines
+}
+
+fn load_panel(history: &History, view: &ViewState, options: &RenderOptions, width: usize, height: usize) -> Vec<String> {
+    let mut lines = vec![trend_line(history, view, options)];
+    let rows = (height.saturating_sub(1) / 3).saturating_sub(1).max(1);
+    let scale = load_scale(history);
+
+    for (label, pick) in load_series() {
+        let values: Vec<f64> = history.samples().filter_map(|sample| sample.load.map(pick)).collect();
+        let current = values.last().map_or_else(|| "n/a".to_string(), |load| format!("{:.2}", load));
+        let peak = values.iter().cloned().fold(0.0, f64::max);
+        lines.push(format!("Load {}: {}  (peak {:.2})", label.trim(), current, peak));
+        lines.extend(chart(&values, width, rows, scale));
+    }
+
+    lines
+}
+
+fn sessions_panel(metrics: &SystemMetrics, height: usize) -> Vec<String> {
+    let Some(sessions) = metrics.sessions() else {
+        return vec!["Sessions: unknown".to_string()];
+    };
+
+    let mut lines = vec![
+        format!("Sessions: {}", sessions.len()),
+        format!("  {:<8} {}", "UID", "TTY"),
+    ];
+    let room = height.saturating_sub(lines.len());
+    let shown = if sessions.len() > room { room.saturating_sub(1) } else { sessions.len() };
+
+    for session in &sessions[..shown] {
+        lines.push(format!("  {:<8} {}", session.uid, session.tty));
+    }
+    if shown < sessions.len() {
+        lines.push(format!("  ... {} more", sessions.len() - shown));
+    }
+
+    lines
+}
+
+/// Direction of the newest load and, once there is enough history, how fast
+/// it moves and when it reaches the threshold:
+/// `Trend ↗ rising, +0.38/min, 8.00 in 4m 10s`
+fn trend_line(history: &History, view: &ViewState, options: &RenderOptions) -> String {
+    let Some(load) = history.latest().and_then(|sample| sample.load) else {
+        return "Trend n/a".to_string();
+    };
+    let trend = LoadTrend::from_averages(load).trend.describe(options.glyphs);
+    let Some(forecast) = history.load_forecast(view.load_threshold) else {
+        return format!("Trend {}", trend);
+    };
+
+    let eta = match forecast.eta_seconds {
+        Some(eta) if eta <= 0.0 => format!("at or above {:.2}", forecast.threshold),
+        Some(eta) => format!("{:.2} in {}", forecast.threshold, options.duration.resolve(DurationStyle::Compact).format(eta)),
+        None => format!("stays below {:.2}", forecast.threshold),
+    };
+    format!("Trend {}, {:+.2}/min, {}", trend, forecast.slope_per_minute, eta)
+}
+
+/// Picks one of the three load averages
+type LoadPick = fn((f64, f64, f64)) -> f64;
+
+/// Labels and accessors for the three load averages
+fn load_series() -> [(&'static str, LoadPick); 3] {
+    [
+        (" 1m", |load| load.0),
+        (" 5m", |load| load.1),
+        ("15m", |load| load.2),
+    ]
+}
+
+/// Shared scale for the three load charts so they can be compared
+///
+/// At least 1.0, so an idle machine looks idle instead of filling the chart.
+fn load_scale(history: &History) -> f64 {
+    history.samples()
+        .filter_map(|sample| sample.load)
+        .flat_map(|(load1, load5, load15)| [load1, load5, load15])
+        .fold(1.0, f64::max)
+}
+
+/// One-line chart of the newest `width` values, where `scale` fills a cell
+pub fn sparkline(values: &[f64], width: usize, scale: f64) -> String {
+    let start = values.len().saturating_sub(width);
+
+    values[start..]
+        .iter()
+        .map(|value| {
+            let level = (value / scale * (SPARK_LEVELS.len() - 1) as f64).round() as usize;
+            SPARK_LEVELS[level.min(SPARK_LEVELS.len() - 1)]
+        })
+        .collect()
+}
+
+/// Multi-row bar chart of the newest `width` values, top row first
+fn chart(values: &[f64], width: usize, rows: usize, scale: f64) -> Vec<String> {
+    let start = values.len().saturating_sub(width);
+    let steps = SPARK_LEVELS.len() - 1;
+
+    (0..rows)
+        .rev()
+        .map(|row| {
+            values[start..]
+                .iter()
+                .map(|value| {
+                    let filled = (value / scale * (rows * steps) as f64).round() as usize;
+                    let level = filled.saturating_sub(row * steps).min(steps);
+                    SPARK_LEVELS[level]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Horizontal gauge such as `[#######.............]  35.2%`
+pub fn gauge(percent: Option<f64>, width: usize) -> String {
+    let Some(percent) = percent else {
+        return format!("[{}]    n/a", ".".repeat(width));
+    };
+    let percent = percent.clamp(0.0, 100.0);
+    let filled = (percent / 100.0 * width as f64).round() as usize;
+
+    format!("[{}{}] {:5.1}%", "#".repeat(filled), ".".repeat(width - filled), percent)
+}
+
+fn format_interval(interval: Duration) -> String {
+    if interval.subsec_millis() == 0 {
+        format!("{}s", interval.as_secs())
+    } else {
+        format!("{:.2}s", interval.as_secs_f64())
+    }
+}
+
+/// Pad or cut a plain line to exactly `width` columns
+fn fit(line: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+
+    for c in line.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if used + char_width > width {
+            break;
+        }
+        out.push(c);
+        used += char_width;
+    }
+
+    out.push_str(&" ".repeat(width - used));
+    out
+}
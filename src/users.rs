@@ -0,0 +1,160 @@
+//! Which accounts count as logged-in users
+//!
+//! Human accounts are recognised by UID range, read from `UID_MIN` and
+//! `UID_MAX` in `/etc/login.defs` like `useradd` does. Root is counted too.
+//! The range can be overridden in the config file, and accounts added to or
+//! removed from the count:
+//!
+//! ```text
+//! [users]
+//! uid_min = 10000
+//! uid_max = 60000
+//! include = ["backup"]
+//! exclude = ["root", 1001]
+//! ```
+//!
+//! `include` adds to root rather than replacing it; list root under
+//! `exclude` to stop counting it.
+//!
+//! Names are resolved through `/etc/passwd`; numbers are taken as UIDs.
+
+use std::collections::BTreeSet;
+use std::fs;
+
+use crate::config::{Config, ConfigValue};
+
+/// First UID of regular accounts when login.defs does not say
+pub const DEFAULT_UID_MIN: u32 = 1000;
+
+/// Last UID of regular accounts when login.defs does not say
+pub const DEFAULT_UID_MAX: u32 = 60000;
+
+/// One line of `/etc/passwd`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswdEntry {
+    pub name: String,
+    pub uid: u32,
+}
+
+/// Parse `/etc/passwd` contents, skipping malformed lines
+pub fn parse_passwd(content: &str) -> Vec<PasswdEntry> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next().filter(|name| !name.is_empty())?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some(PasswdEntry { name: name.to_string(), uid })
+        })
+        .collect()
+}
+
+/// `UID_MIN` and `UID_MAX` from `/etc/login.defs` contents
+pub fn parse_login_defs(content: &str) -> (Option<u32>, Option<u32>) {
+    let mut uid_min = None;
+    let mut uid_max = None;
+
+    for line in content.lines().map(str::trim).filter(|line| !line.starts_with('#')) {
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next().and_then(|value| value.parse().ok())) {
+            (Some("UID_MIN"), Some(value)) => uid_min = Some(value),
+            (Some("UID_MAX"), Some(value)) => uid_max = Some(value),
+            _ => {}
+        }
+    }
+
+    (uid_min, uid_max)
+}
+
+/// Decides which UIDs are counted as users
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserFilter {
+    /// First UID of the regular account range
+    pub uid_min: u32,
+    /// Last UID of the regular account range
+    pub uid_max: u32,
+    /// Always counted, even outside the range
+    pub include: BTreeSet<u32>,
+    /// Never counted; wins over `include` and the range
+    pub exclude: BTreeSet<u32>,
+}
+
+impl Default for UserFilter {
+    /// Default range, counting root too
+    fn default() -> Self {
+        Self {
+            uid_min: DEFAULT_UID_MIN,
+            uid_max: DEFAULT_UID_MAX,
+            include: BTreeSet::from([0]),
+            exclude: BTreeSet::new(),
+        }
+    }
+}
+
+impl UserFilter {
+    /// Filter for this host: `/etc/login.defs`, `/etc/passwd` and the
+    /// `[users]` section of `config`
+    pub fn load(config: &Config) -> Result<Self, String> {
+        let login_defs = fs::read_to_string("/etc/login.defs").unwrap_or_default();
+        let passwd = parse_passwd(&fs::read_to_string("/etc/passwd").unwrap_or_default());
+
+        Self::from_sources(&login_defs, &passwd, config)
+    }
+
+    /// Build a filter from login.defs contents, passwd entries and config
+    pub fn from_sources(login_defs: &str, passwd: &[PasswdEntry], config: &Config) -> Result<Self, String> {
+        let mut filter = Self::default();
+
+        let (uid_min, uid_max) = parse_login_defs(login_defs);
+        filter.uid_min = uid_min.unwrap_or(filter.uid_min);
+        filter.uid_max = uid_max.unwrap_or(filter.uid_max);
+
+        if let Some(uid_min) = config.integer("users.uid_min")? {
+            filter.uid_min = config_uid("users.uid_min", uid_min)?;
+        }
+        if let Some(uid_max) = config.integer("users.uid_max")? {
+            filter.uid_max = config_uid("users.uid_max", uid_max)?;
+        }
+        if let Some(include) = config.get("users.include") {
+            filter.include.extend(resolve_users("users.include", include, passwd)?);
+        }
+        if let Some(exclude) = config.get("users.exclude") {
+            filter.exclude = resolve_users("users.exclude", exclude, passwd)?;
+        }
+
+        Ok(filter)
+    }
+
+    /// Whether processes owned by `uid` count as a logged-in user
+    pub fn matches(&self, uid: u32) -> bool {
+        if self.exclude.contains(&uid) {
+            return false;
+        }
+        self.include.contains(&uid) || (self.uid_min..=self.uid_max).contains(&uid)
+    }
+}
+
+fn config_uid(key: &str, value: i64) -> Result<u32, String> {
+    u32::try_from(value).map_err(|_| format!("`{}` is not a valid UID", key))
+}
+
+/// UIDs for a list of user names and numbers
+fn resolve_users(key: &str, value: &ConfigValue, passwd: &[PasswdEntry]) -> Result<BTreeSet<u32>, String> {
+    let ConfigValue::Array(items) = value else {
+        return Err(format!("`{}` must be a list of user names or UIDs", key));
+    };
+
+    items
+        .iter()
+        .map(|item| match item {
+            ConfigValue::Integer(uid) => config_uid(key, *uid),
+            ConfigValue::String(name) => passwd
+                .iter()
+                .find(|entry| entry.name == *name)
+                .map(|entry| entry.uid)
+                .ok_or_else(|| format!("`{}`: unknown user '{}'", key, name)),
+            _ => Err(format!("`{}` must be a list of user names or UIDs", key)),
+        })
+        .collect()
+}
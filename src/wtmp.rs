@@ -0,0 +1,252 @@
+//! Boot and shutdown history from `/var/log/wtmp`
+//!
+//! wtmp is a sequence of fixed-size `struct utmp` records (384 bytes on
+//! Linux). `last -x` shows the ones used here: `reboot` records of type
+//! `BOOT_TIME` when the system starts, and `shutdown` records of type
+//! `RUN_LVL` when it is brought down cleanly. A boot followed directly by
+//! another boot never shut down cleanly — it crashed or lost power.
+//!
+//! The systemd journal keeps its own boot list; an export made with
+//! `journalctl --utc --list-boots > boots.txt` can be read with
+//! [`parse_list_boots`] and matched to wtmp boots with [`cross_reference`].
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+/// Default location of the login/boot history
+pub const DEFAULT_PATH: &str = "/var/log/wtmp";
+
+/// Size of one `struct utmp` on Linux
+pub const RECORD_SIZE: usize = 384;
+
+/// Journal boots whose first entry is this close to a wtmp boot are the same boot
+const MATCH_WINDOW_SECONDS: i64 = 300;
+
+/// `ut_type` values from `<utmp.h>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordKind {
+    Empty,
+    RunLevel,
+    BootTime,
+    NewTime,
+    OldTime,
+    InitProcess,
+    LoginProcess,
+    UserProcess,
+    DeadProcess,
+    Accounting,
+    Unknown(i16),
+}
+
+impl From<i16> for RecordKind {
+    fn from(value: i16) -> Self {
+        match value {
+            0 => RecordKind::Empty,
+            1 => RecordKind::RunLevel,
+            2 => RecordKind::BootTime,
+            3 => RecordKind::NewTime,
+            4 => RecordKind::OldTime,
+            5 => RecordKind::InitProcess,
+            6 => RecordKind::LoginProcess,
+            7 => RecordKind::UserProcess,
+            8 => RecordKind::DeadProcess,
+            9 => RecordKind::Accounting,
+            other => RecordKind::Unknown(other),
+        }
+    }
+}
+
+/// One wtmp record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WtmpRecord {
+    pub kind: RecordKind,
+    pub pid: i32,
+    pub line: String,
+    pub user: String,
+    /// For boot records, the kernel release
+    pub host: String,
+    pub time: DateTime<Utc>,
+}
+
+impl WtmpRecord {
+    /// Parse one `RECORD_SIZE`-byte record; `None` if the slice is too short
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < RECORD_SIZE {
+            return None;
+        }
+        let i32_at = |offset: usize| i32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let text = |range: std::ops::Range<usize>| {
+            let field = &bytes[range];
+            let end = field.iter().position(|byte| *byte == 0).unwrap_or(field.len());
+            String::from_utf8_lossy(&field[..end]).into_owned()
+        };
+
+        let seconds = i32_at(340);
+        let micros = i32_at(344);
+        Some(Self {
+            kind: RecordKind::from(i16::from_ne_bytes([bytes[0], bytes[1]])),
+            pid: i32_at(4),
+            line: text(8..40),
+            user: text(44..76),
+            host: text(76..332),
+            time: DateTime::from_timestamp(seconds as u32 as i64, micros.clamp(0, 999_999) as u32 * 1000)?,
+        })
+    }
+
+    /// A `reboot` record written when the system starts
+    pub fn is_boot(&self) -> bool {
+        self.kind == RecordKind::BootTime
+    }
+
+    /// A `shutdown` record written by a clean shutdown or reboot
+    pub fn is_shutdown(&self) -> bool {
+        self.kind == RecordKind::RunLevel && self.user == "shutdown"
+    }
+}
+
+/// Every complete record in wtmp contents; a truncated last record is ignored
+pub fn parse_records(bytes: &[u8]) -> Vec<WtmpRecord> {
+    bytes.chunks_exact(RECORD_SIZE).filter_map(WtmpRecord::parse).collect()
+}
+
+/// Read and parse a wtmp file
+pub fn read_records(path: &Path) -> io::Result<Vec<WtmpRecord>> {
+    Ok(parse_records(&fs::read(path)?))
+}
+
+/// How a boot ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootEnd {
+    /// This is the running boot
+    Running,
+    /// A shutdown record was written at this time
+    Clean(DateTime<Utc>),
+    /// The next boot started without a shutdown record
+    Crash,
+}
+
+/// One boot from wtmp, optionally matched to a journal boot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootRecord {
+    pub started: DateTime<Utc>,
+    pub end: BootEnd,
+    /// Kernel release recorded at boot
+    pub kernel: String,
+    /// Journal boot ID, if cross-referenced
+    pub boot_id: Option<String>,
+    /// Last journal entry of this boot, if cross-referenced
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl BootRecord {
+    /// When the boot ended: the shutdown record, else the last journal entry
+    pub fn ended(&self) -> Option<DateTime<Utc>> {
+        match self.end {
+            BootEnd::Clean(time) => Some(time),
+            BootEnd::Crash => self.last_seen,
+            BootEnd::Running => None,
+        }
+    }
+}
+
+/// Boots in `records`, oldest first; the last one is taken to be running
+pub fn boots(records: &[WtmpRecord]) -> Vec<BootRecord> {
+    let mut boots: Vec<BootRecord> = Vec::new();
+
+    for record in records {
+        if record.is_boot() {
+            if let Some(previous) = boots.last_mut() {
+                if previous.end == BootEnd::Running {
+                    previous.end = BootEnd::Crash;
+                }
+            }
+            boots.push(BootRecord {
+                started: record.time,
+                end: BootEnd::Running,
+                kernel: record.host.clone(),
+                boot_id: None,
+                last_seen: None,
+            });
+        } else if record.is_shutdown() {
+            if let Some(current) = boots.last_mut() {
+                if current.end == BootEnd::Running {
+                    current.end = BootEnd::Clean(record.time);
+                }
+            }
+        }
+    }
+
+    boots
+}
+
+/// The boot before the running one, if wtmp reaches back that far
+pub fn previous_boot(boots: &[BootRecord]) -> Option<&BootRecord> {
+    boots.len().checked_sub(2).map(|index| &boots[index])
+}
+
+/// One line of `journalctl --utc --list-boots`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalBoot {
+    /// 0 for the current boot, negative for earlier ones
+    pub index: i64,
+    pub boot_id: String,
+    pub first_entry: DateTime<Utc>,
+    pub last_entry: DateTime<Utc>,
+}
+
+/// Parse `journalctl --utc --list-boots` output
+///
+/// Timestamps must be in UTC; local time zone abbreviations are ambiguous
+/// and rejected.
+pub fn parse_list_boots(text: &str) -> Result<Vec<JournalBoot>, String> {
+    let mut boots = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() || words[0] == "IDX" {
+            continue;
+        }
+        let error = || format!("line {}: expected `IDX BOOT-ID FIRST-ENTRY LAST-ENTRY` in UTC", number + 1);
+        if words.len() != 10 {
+            return Err(error());
+        }
+
+        let timestamp = |date: &str, time: &str, zone: &str| {
+            if zone != "UTC" {
+                return None;
+            }
+            NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|naive| naive.and_utc())
+        };
+        boots.push(JournalBoot {
+            index: words[0].parse().map_err(|_| error())?,
+            boot_id: words[1].to_string(),
+            first_entry: timestamp(words[3], words[4], words[5]).ok_or_else(error)?,
+            last_entry: timestamp(words[7], words[8], words[9]).ok_or_else(error)?,
+        });
+    }
+
+    Ok(boots)
+}
+
+/// Attach journal boot IDs and last-entry times to the matching wtmp boots
+///
+/// A journal boot matches when its first entry is within five minutes of the
+/// wtmp boot record.
+pub fn cross_reference(boots: &mut [BootRecord], journal: &[JournalBoot]) {
+    for boot in boots.iter_mut() {
+        let matching = journal
+            .iter()
+            .filter(|entry| (entry.first_entry - boot.started).num_seconds().abs() <= MATCH_WINDOW_SECONDS)
+            .min_by_key(|entry| (entry.first_entry - boot.started).num_seconds().abs());
+
+        if let Some(entry) = matching {
+            boot.boot_id = Some(entry.boot_id.clone());
+            boot.last_seen = Some(entry.last_entry);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use common::{scratch_root, stat, write};
+use runtime::cgroup::{parse_cpu_usage, CgroupStats, CgroupTarget, Pressure};
+
+/// nginx.service with a master started 500s after boot and a worker in a sub-cgroup
+fn nginx_root(name: &str) -> PathBuf {
+    let root = scratch_root(name);
+    let unit = "sys/fs/cgroup/system.slice/nginx.service";
+    write(&root, "sys/fs/cgroup/cgroup.controllers", "cpu io memory pids\n");
+    write(&root, &format!("{}/cgroup.procs", unit), "812\n");
+    write(&root, &format!("{}/workers/cgroup.procs", unit), "813\n");
+    write(&root, &format!("{}/cpu.stat", unit), "usage_usec 3723500000\nuser_usec 3000000000\nsystem_usec 723500000\n");
+    write(&root, &format!("{}/memory.current", unit), "126353408\n");
+    write(&root, &format!("{}/memory.max", unit), "max\n");
+    write(&root, &format!("{}/cpu.pressure", unit),
+        "some avg10=0.12 avg60=0.08 avg300=0.05 total=123456\nfull avg10=0.00 avg60=0.00 avg300=0.00 total=0\n");
+    write(&root, "proc/stat", "cpu  1 2 3 4\nbtime 1709280000\nprocesses 9000\n");
+    write(&root, "proc/812/stat", &stat(812, "nginx", 0, 500));
+    write(&root, "proc/813/stat", &stat(813, "nginx: worker (x)", 0, 900));
+    root
+}
+
+#[test]
+fn test_parse_cgroup_files() {
+    assert_eq!(Pressure::parse("some avg10=1.50 avg60=0.75 avg300=0.25 total=99\n"),
+        Some(Pressure { avg10: 1.5, avg60: 0.75, avg300: 0.25 }));
+    assert_eq!(Pressure::parse("full avg10=0.00 avg60=0.00 avg300=0.00 total=0\n"), None);
+    assert_eq!(parse_cpu_usage("usage_usec 2500000\nuser_usec 2000000\n"), Some(2.5));
+
+    assert_eq!(CgroupTarget::Unit("nginx".to_string()).name(), "nginx.service");
+    assert_eq!(CgroupTarget::Unit("backup.timer".to_string()).name(), "backup.timer");
+}
+
+#[test]
+fn test_unit_uptime_from_oldest_process() {
+    let root = nginx_root("unit");
+    let now = DateTime::from_timestamp(1_709_280_000 + 500 + 3600, 0).unwrap();
+
+    let stats = CgroupStats::read(&CgroupTarget::Unit("nginx".to_string()), &root, now).unwrap();
+    assert_eq!(stats.name, "nginx.service");
+    assert_eq!(stats.path, root.join("sys/fs/cgroup/system.slice/nginx.service"));
+    assert_eq!(stats.main_pid, Some(812));
+    assert_eq!(stats.started, DateTime::from_timestamp(1_709_280_500, 0));
+    assert_eq!(stats.uptime_seconds, Some(3600.0));
+    assert_eq!(stats.cpu_seconds, Some(3723.5));
+    assert_eq!(stats.memory_bytes, Some(126_353_408));
+    assert_eq!(stats.memory_max, None, "max means unlimited");
+    assert_eq!(stats.cpu_pressure.map(|pressure| pressure.avg10), Some(0.12));
+    assert_eq!(stats.io_pressure, None);
+}
+
+#[test]
+fn test_cgroup_paths() {
+    let root = nginx_root("paths");
+    let dir = root.join("sys/fs/cgroup/system.slice/nginx.service");
+
+    for path in ["/sys/fs/cgroup/system.slice/nginx.service", "/system.slice/nginx.service", "system.slice/nginx.service"] {
+        assert_eq!(CgroupTarget::Path(PathBuf::from(path)).resolve(&root).unwrap(), dir, "{}", path);
+    }
+    assert!(CgroupTarget::Path(PathBuf::from("/system.slice/missing.service")).resolve(&root).is_err());
+
+    let error = CgroupTarget::Unit("postgresql".to_string()).resolve(&root).unwrap_err();
+    assert_eq!(error.to_string(), "unit postgresql.service has no cgroup (is it running?)");
+}
@@ -0,0 +1,42 @@
+//! Fixture helpers shared by the integration tests
+//!
+//! Each test binary uses a different subset, hence the `dead_code` allowance.
+#![allow(dead_code)]
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use runtime::proc_scan::clock_ticks;
+
+/// An empty root directory unique to one test
+pub fn scratch_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir()
+        .join(format!("runtime-{}-{}-{}", env!("CARGO_CRATE_NAME"), name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    root
+}
+
+/// Create `path` below `root` with `content`, and any missing parent directories
+pub fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/// Like [`write`], last modified at `unix_seconds`
+pub fn write_at(root: &Path, path: &str, content: &str, unix_seconds: u64) {
+    write(root, path, content);
+    File::options().write(true).open(root.join(path)).unwrap()
+        .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(unix_seconds))
+        .unwrap();
+}
+
+/// A `/proc/<pid>/stat` line on terminal `tty_nr` (0 for none), started
+/// `seconds_after_boot`, with 90s of user and 30s of system CPU time
+pub fn stat(pid: u32, comm: &str, tty_nr: i32, seconds_after_boot: u64) -> String {
+    let ticks = clock_ticks();
+    format!("{} ({}) S 1 {} {} {} -1 4194560 100 0 0 0 {} {} 0 0 20 0 1 0 {} 12345678 200 18446744073709551615\n",
+        pid, comm, pid, pid, tty_nr, 90 * ticks, 30 * ticks, seconds_after_boot * ticks)
+}
@@ -0,0 +1,38 @@
+use runtime::config::{Config, ConfigValue};
+
+#[test]
+fn test_config_values_and_sections() {
+    let config = Config::parse(r##"
+        # loading spinner
+        quiet = true
+        spinner_delay_ms = 1_000   # one second
+        theme = "light # not a comment"
+
+        [users]
+        exclude = ["nobody", "guest"]
+    "##).unwrap();
+
+    assert_eq!(config.bool("quiet"), Ok(Some(true)));
+    assert_eq!(config.integer("spinner_delay_ms"), Ok(Some(1000)));
+    assert_eq!(config.string("theme"), Ok(Some("light # not a comment")));
+    assert_eq!(config.get("users.exclude"), Some(&ConfigValue::Array(vec![
+        ConfigValue::String("nobody".to_string()),
+        ConfigValue::String("guest".to_string()),
+    ])));
+    assert_eq!(config.bool("missing"), Ok(None));
+}
+
+#[test]
+fn test_config_type_errors() {
+    let config = Config::parse("quiet = \"yes\"").unwrap();
+    assert!(config.bool("quiet").is_err());
+
+    assert!(Config::parse("quiet").unwrap_err().contains("line 1"));
+    assert!(Config::parse("quiet = maybe").unwrap_err().contains("invalid value"));
+}
+
+#[test]
+fn test_missing_config_file_is_empty() {
+    let config = Config::from_file(std::path::Path::new("/nonexistent/runtime/config.toml")).unwrap();
+    assert_eq!(config, Config::default());
+}
@@ -0,0 +1,92 @@
+use runtime::duration::{parse_duration, DurationFormat, DurationSettings, DurationStyle, DurationUnit};
+
+/// 3 days, 4 hours, 12 minutes, 5 seconds
+const UPTIME: f64 = 274_325.0;
+
+#[test]
+fn test_styles() {
+    assert_eq!(DurationFormat::new(DurationStyle::Compact).format(UPTIME), "3d 4h 12m 5s");
+    assert_eq!(DurationFormat::new(DurationStyle::Long).format(UPTIME), "3 days, 4 hours, 12 minutes");
+    assert_eq!(DurationFormat::new(DurationStyle::Procps).format(UPTIME), "3 days,  4:12");
+    assert_eq!(DurationFormat::new(DurationStyle::Iso8601).format(UPTIME), "P3DT4H12M5S");
+    assert_eq!(DurationFormat::new(DurationStyle::Clock).format(UPTIME), "76:12:05");
+}
+
+#[test]
+fn test_zero_and_short_durations() {
+    assert_eq!(DurationFormat::new(DurationStyle::Compact).format(0.0), "0s");
+    assert_eq!(DurationFormat::new(DurationStyle::Long).format(42.0), "less than a minute");
+    assert_eq!(DurationFormat::new(DurationStyle::Procps).format(42.0), "0 min");
+    assert_eq!(DurationFormat::new(DurationStyle::Iso8601).format(0.0), "PT0S");
+    assert_eq!(DurationFormat::new(DurationStyle::Clock).format(5.0), "0:00:05");
+}
+
+#[test]
+fn test_weeks_and_plurals() {
+    let long = DurationFormat::new(DurationStyle::Long);
+    assert_eq!(long.format(40.0 * 86400.0 + 3600.0 + 60.0), "5 weeks, 5 days, 1 hour, 1 minute");
+    assert_eq!(long.format(7.0 * 86400.0), "1 week");
+}
+
+#[test]
+fn test_unit_limits() {
+    let compact = DurationFormat::new(DurationStyle::Compact);
+    assert_eq!(compact.largest(DurationUnit::Hours).format(UPTIME), "76h 12m 5s");
+    assert_eq!(compact.smallest(DurationUnit::Hours).format(UPTIME), "3d 4h");
+    assert_eq!(compact.largest(DurationUnit::Weeks).format(21.0 * 86400.0), "3w");
+
+    let procps = DurationFormat::new(DurationStyle::Procps);
+    assert_eq!(procps.largest(DurationUnit::Hours).format(UPTIME), "76:12");
+    assert_eq!(procps.largest(DurationUnit::Minutes).format(UPTIME), "4572 min");
+    assert_eq!(procps.smallest(DurationUnit::Seconds).format(UPTIME), "3 days,  4:12:05");
+    assert_eq!(procps.smallest(DurationUnit::Seconds).format(185.0), "3 min, 5 sec");
+    assert_eq!(procps.smallest(DurationUnit::Days).format(UPTIME), "3 days");
+    assert_eq!(procps.smallest(DurationUnit::Days).format(3600.0), "0 days");
+    assert_eq!(procps.largest(DurationUnit::Weeks).format(10.0 * 86400.0), "1 week, 3 days, 0 min");
+
+    let clock = DurationFormat::new(DurationStyle::Clock);
+    assert_eq!(clock.largest(DurationUnit::Days).format(UPTIME), "3:04:12:05");
+    assert_eq!(clock.smallest(DurationUnit::Minutes).format(UPTIME), "76:12");
+
+    let iso = DurationFormat::new(DurationStyle::Iso8601).smallest(DurationUnit::Hours);
+    assert_eq!(iso.format(UPTIME), "P3DT4H");
+    assert_eq!(iso.format(3.0 * 86400.0), "P3D");
+
+    // Weeks may not be combined with other units, so they become days
+    let iso_weeks = DurationFormat::new(DurationStyle::Iso8601).largest(DurationUnit::Weeks);
+    assert_eq!(iso_weeks.format(40.0 * 86400.0 + 3660.0), "P40DT1H1M");
+    assert_eq!(iso_weeks.format(35.0 * 86400.0), "P5W");
+    assert_eq!(DurationFormat::new(DurationStyle::Iso8601).format(40.0 * 86400.0 + 3660.0), "P40DT1H1M");
+}
+
+#[test]
+fn test_settings_override_default_style() {
+    let settings = DurationSettings {
+        style: None,
+        largest: Some(DurationUnit::Hours),
+        smallest: None,
+    };
+    assert_eq!(settings.resolve(DurationStyle::Long).format(UPTIME), "76 hours, 12 minutes");
+
+    let settings = DurationSettings { style: Some(DurationStyle::Compact), ..settings };
+    assert_eq!(settings.resolve(DurationStyle::Long).format(UPTIME), "76h 12m 5s");
+}
+
+#[test]
+fn test_parse_names() {
+    assert_eq!("iso".parse::<DurationStyle>(), Ok(DurationStyle::Iso8601));
+    assert_eq!("days".parse::<DurationUnit>(), Ok(DurationUnit::Days));
+    assert!("fortnights".parse::<DurationUnit>().is_err());
+}
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(parse_duration("30d"), Ok(30 * 86400));
+    assert_eq!(parse_duration("36h"), Ok(36 * 3600));
+    assert_eq!(parse_duration("2w 3d"), Ok(17 * 86400));
+    assert_eq!(parse_duration("1 day, 12 hours"), Ok(36 * 3600));
+    assert!(parse_duration("").is_err());
+    assert!(parse_duration("30").is_err());
+    assert!(parse_duration("d").is_err());
+    assert!(parse_duration("99999999999999999999w").is_err());
+}
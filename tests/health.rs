@@ -0,0 +1,90 @@
+use chrono::DateTime;
+use runtime::clock::Clock;
+use runtime::health::{self, CheckFormat, Finding, Verdict, EXIT_FAILED, EXIT_OK, EXIT_UNKNOWN};
+use runtime::reboot::{RebootReason, RebootStatus};
+use runtime::render::RenderOptions;
+use runtime::system_metrics::SystemMetrics;
+use runtime::time_format::{TimeSettings, TimeZoneChoice};
+
+fn options() -> RenderOptions {
+    RenderOptions {
+        time: TimeSettings { zone: TimeZoneChoice::Utc, ..TimeSettings::default() },
+        clock: Clock::Fixed(DateTime::from_timestamp(1_709_280_000, 0).unwrap()),
+        ..RenderOptions::default()
+    }
+}
+
+#[test]
+fn test_pending_reboot_check() {
+    let pending = RebootStatus {
+        reasons: vec![RebootReason::KernelUpgrade { running: "6.1.0-18-amd64".to_string(), installed: "6.1.0-20-amd64".to_string() }],
+        since: DateTime::from_timestamp(1_709_280_000, 0),
+    };
+
+    let finding = health::pending_reboot(&SystemMetrics::default().with_reboot(pending), &options());
+    assert_eq!(finding.verdict, Verdict::Fail);
+    assert_eq!(finding.line(), "FAIL pending-reboot: reboot pending since 2024-03-01 08:00:00 (kernel 6.1.0-20-amd64 installed)");
+
+    let clean = health::pending_reboot(&SystemMetrics::default().with_reboot(RebootStatus::default()), &options());
+    assert_eq!(clean.line(), "OK pending-reboot: no reboot pending");
+    assert_eq!(health::pending_reboot(&SystemMetrics::default(), &options()).verdict, Verdict::Unknown);
+}
+
+#[test]
+fn test_exit_code_is_worst_verdict() {
+    let finding = |verdict| Finding { check: "test", verdict, message: String::new(), standing: None };
+
+    assert_eq!(health::exit_code(&[]), EXIT_OK);
+    assert_eq!(health::exit_code(&[finding(Verdict::Pass), finding(Verdict::Unknown)]), EXIT_UNKNOWN);
+    assert_eq!(health::exit_code(&[finding(Verdict::Fail), finding(Verdict::Unknown)]), EXIT_FAILED);
+}
+
+#[test]
+fn test_max_uptime_policy() {
+    const DAY: f64 = 86400.0;
+
+    let within = health::max_uptime(&SystemMetrics::default().with_uptime_seconds(12.0 * DAY), 30.0 * DAY, &options());
+    assert_eq!(within.line(), "OK max-uptime: up 12.0 days, limit 30.0 days: 18.0 days remaining, reboot by 2024-03-19");
+    let standing = within.standing.unwrap();
+    assert_eq!(standing.days_remaining(), 18.0);
+    assert_eq!(standing.suggested_maintenance, standing.deadline);
+
+    let overdue = health::max_uptime(&SystemMetrics::default().with_uptime_seconds(34.5 * DAY), 30.0 * DAY, &options());
+    assert_eq!(overdue.verdict, Verdict::Fail);
+    assert_eq!(overdue.line(), "FAIL max-uptime: up 34.5 days, limit 30.0 days: 4.5 days overdue since 2024-02-25, reboot as soon as possible");
+    let standing = overdue.standing.unwrap();
+    assert_eq!(standing.days_remaining(), -4.5);
+    assert_eq!(standing.suggested_maintenance, options().clock.now());
+
+    let unmeasured = health::max_uptime(&SystemMetrics::default(), 30.0 * DAY, &options());
+    assert_eq!(unmeasured.verdict, Verdict::Unknown);
+    assert_eq!(unmeasured.standing, None);
+}
+
+#[test]
+fn test_check_reports() {
+    let findings = [
+        health::pending_reboot(&SystemMetrics::default().with_reboot(RebootStatus::default()), &options()),
+        health::max_uptime(&SystemMetrics::default().with_uptime_seconds(40.0 * 86400.0), 30.0 * 86400.0, &options()),
+    ];
+
+    let text = health::report(&findings, CheckFormat::Text);
+    assert_eq!(text.lines().count(), 2);
+    assert!(text.starts_with("OK pending-reboot: "));
+
+    let json: serde_json::Value = serde_json::from_str(&health::report(&findings, CheckFormat::Json)).unwrap();
+    assert_eq!(json["verdict"], "fail");
+    assert_eq!(json["exit_code"], EXIT_FAILED);
+    assert_eq!(json["findings"][1]["days_remaining"], -10.0);
+    assert_eq!(json["findings"][1]["deadline"], "2024-02-20T08:00:00Z");
+    assert_eq!(json["findings"][1]["suggested_maintenance"], "2024-03-01T08:00:00Z");
+
+    let sarif: serde_json::Value = serde_json::from_str(&health::report(&findings, CheckFormat::Sarif)).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    let results = &sarif["runs"][0]["results"];
+    assert_eq!(results[0]["kind"], "pass");
+    assert_eq!(results[1]["ruleId"], "max-uptime");
+    assert_eq!(results[1]["level"], "error");
+
+    assert!("yaml".parse::<CheckFormat>().is_err());
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::{scratch_root, write};
+use runtime::host_info::{detect_virtualization, HostInfo, OsRelease, Virtualization};
+
+fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/host")
+}
+
+#[test]
+fn test_reads_host_fixture() {
+    let host = HostInfo::read(&fixture());
+
+    assert_eq!(host.hostname.as_deref(), Some("web-01"));
+    assert_eq!(host.kernel_release.as_deref(), Some("6.1.0-18-amd64"));
+    assert_eq!(host.architecture.as_deref(), Some("x86_64"));
+    assert_eq!(host.virtualization, Some(Virtualization::Vm(Some("qemu".to_string()))));
+
+    let os = host.os.expect("os-release should be parsed");
+    assert_eq!(os.id.as_deref(), Some("debian"));
+    assert_eq!(os.name, "Debian GNU/Linux");
+    assert_eq!(os.version.as_deref(), Some("12 (bookworm)"));
+    assert_eq!(os.display_name(), "Debian GNU/Linux 12 (bookworm)");
+}
+
+#[test]
+fn test_os_release_quoting_and_fallbacks() {
+    let os = OsRelease::parse("NAME='Alpine Linux'\nVERSION_ID=3.19.1\nID=alpine\n").unwrap();
+    assert_eq!(os.display_name(), "Alpine Linux 3.19.1");
+
+    let os = OsRelease::parse(r#"PRETTY_NAME="Say \"hi\" OS""#).unwrap();
+    assert_eq!(os.name, r#"Say "hi" OS"#);
+    assert_eq!(OsRelease::parse("ID=mystery\n"), None);
+}
+
+#[test]
+fn test_missing_files_are_unknown() {
+    let root = scratch_root("empty");
+    let host = HostInfo::read(&root);
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(host.hostname, None);
+    assert_eq!(host.kernel_release, None);
+    assert_eq!(host.os, None);
+    assert_eq!(host.architecture.as_deref(), Some(std::env::consts::ARCH));
+    assert_eq!(host.virtualization, Some(Virtualization::None));
+}
+
+#[test]
+fn test_detect_virtualization() {
+    let root = scratch_root("virt");
+    write(&root, "proc/cpuinfo", "processor\t: 0\nflags\t\t: fpu vme hypervisor lahf_lm\n");
+    assert_eq!(detect_virtualization(&root), Virtualization::Vm(None));
+
+    write(&root, "sys/class/dmi/id/product_name", "Virtual Machine\n");
+    assert_eq!(detect_virtualization(&root).describe(), "microsoft (vm)");
+
+    write(&root, "proc/1/environ", "HOME=/\0container=lxc\0");
+    assert_eq!(detect_virtualization(&root), Virtualization::Container(Some("lxc".to_string())));
+
+    write(&root, ".dockerenv", "");
+    assert_eq!(detect_virtualization(&root).describe(), "docker (container)");
+
+    write(&root, "run/systemd/container", "systemd-nspawn\n");
+    assert_eq!(detect_virtualization(&root).name(), Some("systemd-nspawn"));
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,37 @@
+use runtime::glyphs::Glyphs;
+use runtime::load_trend::{Forecast, LoadTrend, Trend};
+
+#[test]
+fn test_trend_from_averages() {
+    assert_eq!(LoadTrend::from_averages((1.0, 0.5, 0.25)).trend, Trend::Rising);
+    assert_eq!(LoadTrend::from_averages((4.0, 8.25, 16.5)).trend, Trend::Falling);
+    assert_eq!(LoadTrend::from_averages((0.0, 0.0, 0.0)).trend, Trend::Steady);
+    // Small wiggles on a busy host are not movement
+    assert_eq!(LoadTrend::from_averages((16.5, 16.0, 15.8)).trend, Trend::Steady);
+    // A spike that has not reached the 15-minute average yet is mixed
+    assert_eq!(LoadTrend::from_averages((2.0, 1.0, 3.0)).trend, Trend::Steady);
+
+    let trend = LoadTrend::from_averages((1.0, 0.5, 0.25));
+    assert_eq!((trend.short_term, trend.long_term), (0.5, 0.75));
+    assert_eq!(Trend::Rising.describe(Glyphs::Ascii), "^ rising");
+    assert_eq!(Trend::Steady.arrow(Glyphs::Ascii), '-');
+    assert_eq!(Trend::Falling.describe(Glyphs::Unicode), "↘ falling");
+}
+
+#[test]
+fn test_forecast_eta() {
+    // +0.1 load every 10 seconds: +0.6/min
+    let rising: Vec<(f64, f64)> = (0..10).map(|step| (step as f64 * 10.0, 1.0 + step as f64 * 0.1)).collect();
+    let forecast = Forecast::fit(&rising, 4.0).unwrap();
+    assert!((forecast.slope_per_minute - 0.6).abs() < 1e-9);
+    assert!((forecast.eta_seconds.unwrap() - 210.0).abs() < 1e-6);
+
+    assert_eq!(Forecast::fit(&rising, 1.5).unwrap().eta_seconds, Some(0.0), "already above");
+    assert_eq!(Forecast::fit(&rising, 1000.0).unwrap().eta_seconds, None, "more than a day away");
+
+    let falling: Vec<(f64, f64)> = rising.iter().map(|(time, load)| (*time, 3.0 - load)).collect();
+    assert_eq!(Forecast::fit(&falling, 4.0).unwrap().eta_seconds, None);
+
+    assert_eq!(Forecast::fit(&rising[..2], 4.0), None, "too few samples");
+    assert_eq!(Forecast::fit(&[(5.0, 1.0), (5.0, 2.0), (5.0, 3.0)], 4.0), None, "no time spread");
+}
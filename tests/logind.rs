@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use chrono::DateTime;
+use runtime::clock::Clock;
+use runtime::logind::{Logind, LogindSession, SessionClass, SessionState};
+use runtime::system_metrics::{CollectOptions, MetricSet, SystemMetrics};
+
+fn fixture() -> Logind {
+    Logind::read(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/logind")).unwrap()
+}
+
+#[test]
+fn test_reads_every_session_and_user() {
+    let logind = fixture();
+
+    let ids: Vec<&str> = logind.sessions.iter().map(|session| session.id.as_str()).collect();
+    assert_eq!(ids, ["3", "5", "6", "7", "8", "c1"], "*.ref files are skipped");
+    let uids: Vec<u32> = logind.users.iter().map(|user| user.uid).collect();
+    assert_eq!(uids, [1000, 1001]);
+    assert_eq!(logind.users[0].sessions, ["3", "5"]);
+    assert_eq!(logind.users[1].state, SessionState::Closing);
+}
+
+#[test]
+fn test_session_fields() {
+    let logind = fixture();
+    let ssh = &logind.sessions[0];
+
+    assert_eq!(ssh, &LogindSession {
+        id: "3".to_string(),
+        uid: 1000,
+        user: Some("alice".to_string()),
+        seat: None,
+        tty: Some("pts/0".to_string()),
+        remote_host: Some("10.0.0.5".to_string()),
+        class: SessionClass::User,
+        state: SessionState::Active,
+        started: DateTime::from_timestamp_micros(1_709_290_000_123_456),
+    });
+
+    let desktop = &logind.sessions[1];
+    assert_eq!((desktop.seat.as_deref(), desktop.tty.as_deref()), (Some("seat0"), None));
+    assert_eq!(desktop.state, SessionState::Online);
+}
+
+#[test]
+fn test_logins_skip_greeters_background_and_closing() {
+    let logind = fixture();
+    let logins: Vec<&str> = logind.logins().map(|session| session.id.as_str()).collect();
+    assert_eq!(logins, ["3", "5", "6"]);
+}
+
+#[test]
+fn test_missing_logind_is_an_error() {
+    assert!(Logind::read(Path::new("/nonexistent/systemd")).is_err());
+    assert_eq!(LogindSession::parse("9", "USER=nobody\n"), None, "sessions need a UID");
+}
+
+#[test]
+fn test_logind_is_the_first_user_source() {
+    let options = CollectOptions {
+        metrics: MetricSet::USERS,
+        logind_root: Some(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/logind")),
+        ..CollectOptions::default()
+    };
+    let mut metrics = SystemMetrics::default();
+    metrics.refresh_selected(&options, &Clock::System, &mut |_| {}).unwrap();
+
+    // alice over ssh and on seat0, root on tty1
+    assert_eq!(metrics.user_count(), Some(2));
+    assert_eq!(metrics.session_count(), Some(3));
+    assert_eq!(metrics.tty_count(), Some(2));
+    let ttys: Vec<&str> = metrics.sessions().unwrap().iter().map(|session| session.tty.as_str()).collect();
+    assert_eq!(ttys, ["tty1", "pts/0", "seat0"]);
+}
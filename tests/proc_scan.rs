@@ -0,0 +1,95 @@
+mod common;
+
+use std::fs;
+use std::os::unix::fs::{symlink, MetadataExt};
+use std::path::PathBuf;
+
+use common::{scratch_root, stat, write};
+use runtime::proc_scan::{
+    parse_btime, parse_stat_comm, parse_stat_cpu_ticks, parse_stat_session, parse_stat_starttime, parse_stat_tty, ProcScanner,
+};
+
+/// A throwaway procfs with the given `(pid, stat tty_nr)` processes
+fn procfs(name: &str, processes: &[(u32, i32)]) -> PathBuf {
+    let root = scratch_root(name);
+
+    for (pid, tty_nr) in processes {
+        write(&root, &format!("{}/stat", pid), &stat(*pid, "sh", *tty_nr, 0));
+        fs::create_dir_all(root.join(format!("{}/fd", pid))).unwrap();
+    }
+    fs::create_dir_all(root.join("self")).unwrap();
+    root
+}
+
+#[test]
+fn test_parse_stat_tty() {
+    assert_eq!(parse_stat_tty("42 (bash) S 1 42 42 34817 42 4194560"), Some(34817));
+    // Command names may contain spaces and parentheses
+    assert_eq!(parse_stat_tty("42 (my (odd) cmd) S 1 42 42 1025 42"), Some(1025));
+    assert_eq!(parse_stat_tty("42 (bash) S 1"), None);
+    assert_eq!(parse_stat_tty(""), None);
+    assert_eq!(parse_stat_session("42 (my (odd) cmd) S 1 40 41 1025 42"), Some(41));
+}
+
+#[test]
+fn test_scan_finds_terminal_processes() {
+    let root = procfs("scan", &[(1, 0), (20, 34816), (300, 1025)]);
+    let uid = fs::metadata(&root).unwrap().uid();
+
+    for scanner in [ProcScanner::new(&root), ProcScanner::new(&root).threads(3)] {
+        let mut found: Vec<(u32, i32, i32)> = scanner.terminal_processes()
+            .unwrap()
+            .into_iter()
+            .map(|process| (process.pid, process.session, process.tty_nr))
+            .collect();
+        found.sort();
+        assert_eq!(found, [(20, 20, 34816), (300, 300, 1025)]);
+    }
+    assert!(ProcScanner::new(&root).terminal_processes().unwrap().iter().all(|process| process.uid == uid));
+
+    let mut pids = ProcScanner::new(&root).pids().unwrap();
+    pids.sort();
+    assert_eq!(pids, [1, 20, 300], "non-numeric entries are skipped");
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_scan_reports_directory_owner_not_status_uid() {
+    let root = procfs("owner", &[(20, 34816)]);
+    let owner = fs::metadata(root.join("20")).unwrap().uid();
+    let real = owner.wrapping_add(4242);
+    write(&root, "20/status", &format!("Name:\tsh\nUid:\t{real}\t{owner}\t{owner}\t{owner}\n"));
+
+    let found = ProcScanner::new(&root).terminal_processes().unwrap();
+    assert_eq!(found.iter().map(|process| process.uid).collect::<Vec<_>>(), [owner], "effective uid, not the real uid");
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_fd_fallback_checks_standard_descriptors() {
+    let root = procfs("fd", &[(7, 0), (8, 0)]);
+    symlink("/dev/pts/3", root.join("7/fd/2")).unwrap();
+    symlink("/dev/pts/4", root.join("8/fd/9")).unwrap();
+
+    let found = ProcScanner::new(&root).fd_terminals().unwrap();
+    assert_eq!(found.into_iter().map(|(_, tty)| tty).collect::<Vec<_>>(), ["pts/3"]);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_missing_root_is_an_error() {
+    assert!(ProcScanner::new("/nonexistent/proc").terminal_processes().is_err());
+}
+
+#[test]
+fn test_parse_start_fields() {
+    let stat = "812 (my (odd) cmd) S 1 812 812 0 -1 4194560 100 0 0 0 150 50 0 0 20 0 1 0 42000 12345678 200";
+    assert_eq!(parse_stat_comm(stat), Some("my (odd) cmd"));
+    assert_eq!(parse_stat_cpu_ticks(stat), Some(200));
+    assert_eq!(parse_stat_starttime(stat), Some(42000));
+    assert_eq!(parse_stat_starttime("812 (sh) S 1"), None);
+    assert_eq!(parse_btime("cpu  1 2 3\nbtime 1709280000\nprocesses 9\n"), Some(1_709_280_000));
+}
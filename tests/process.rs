@@ -0,0 +1,58 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use chrono::DateTime;
+use common::{scratch_root, stat, write};
+use runtime::process::{ProcessInfo, ProcessTarget};
+
+/// A root with `/proc/stat` and the given `(pid, comm, argv0, seconds after boot)` processes
+fn proc_root(name: &str, processes: &[(u32, &str, &str, u64)]) -> PathBuf {
+    let root = scratch_root(name);
+    write(&root, "proc/stat", "cpu  1 2 3 4\nbtime 1709280000\n");
+
+    for (pid, comm, argv0, seconds_after_boot) in processes {
+        write(&root, &format!("proc/{}/stat", pid), &stat(*pid, comm, 0, *seconds_after_boot));
+        write(&root, &format!("proc/{}/cmdline", pid), &format!("{}\0--flag\0", argv0));
+    }
+    root
+}
+
+fn names(root: &Path, name: &str) -> Vec<u32> {
+    ProcessTarget::Name(name.to_string()).find(root).unwrap()
+}
+
+#[test]
+fn test_process_start_and_cpu_time() {
+    let now = DateTime::from_timestamp(1_709_280_000 + 7200, 0).unwrap();
+    let process = ProcessInfo::from_stat(812, &stat(812, "my (odd) daemon", 0, 600), Some(1_709_280_000), now);
+
+    assert_eq!(process.name, "my (odd) daemon");
+    assert_eq!(process.started, DateTime::from_timestamp(1_709_280_600, 0));
+    assert_eq!(process.uptime_seconds, Some(6600.0));
+    assert_eq!(process.cpu_seconds, Some(120.0));
+
+    let unknown_boot = ProcessInfo::from_stat(812, &stat(812, "daemon", 0, 600), None, now);
+    assert_eq!(unknown_boot.uptime_seconds, None);
+}
+
+#[test]
+fn test_find_by_pid_and_name() {
+    let root = proc_root("find", &[
+        (300, "nginx", "/usr/sbin/nginx", 10),
+        (120, "nginx", "nginx: worker process", 20),
+        (400, "postgres-monito", "/usr/lib/postgresql/bin/postgres-monitor", 30),
+    ]);
+
+    assert_eq!(names(&root, "nginx"), [120, 300]);
+    assert_eq!(names(&root, "postgres-monitor"), [400], "names longer than comm match argv[0]");
+    assert_eq!(ProcessTarget::Pid(300).find(&root).unwrap(), [300]);
+
+    assert_eq!(ProcessTarget::Pid(999).find(&root).unwrap_err().to_string(), "no process with PID 999");
+    assert_eq!(ProcessTarget::Name("redis".to_string()).find(&root).unwrap_err().to_string(), "no process named redis");
+
+    let now = DateTime::from_timestamp(1_709_280_000 + 3600, 0).unwrap();
+    let processes = ProcessInfo::read_all(&ProcessTarget::Name("nginx".to_string()), &root, now).unwrap();
+    let uptimes: Vec<Option<f64>> = processes.iter().map(|process| process.uptime_seconds).collect();
+    assert_eq!(uptimes, [Some(3580.0), Some(3590.0)]);
+}
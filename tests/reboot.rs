@@ -0,0 +1,85 @@
+mod common;
+
+use std::cmp::Ordering;
+use std::fs;
+
+use chrono::DateTime;
+use common::{scratch_root, write_at as write};
+use runtime::reboot::{compare_versions, parse_proc_version, RebootReason, RebootStatus};
+
+#[test]
+fn test_compare_versions() {
+    assert_eq!(compare_versions("6.1.0-18-amd64", "6.1.0-9-amd64"), Ordering::Greater);
+    assert_eq!(compare_versions("5.15.0-101-generic", "6.1.0-1-generic"), Ordering::Less);
+    assert_eq!(compare_versions("6.8.1-arch1-1", "6.8.1-arch1-1"), Ordering::Equal);
+    assert_eq!(compare_versions("6.8.1", "6.8.1-arch1"), Ordering::Less);
+    assert_eq!(parse_proc_version("Linux version 6.1.0-18-amd64 (debian-kernel@lists.debian.org) (gcc-12) #1 SMP"),
+        Some("6.1.0-18-amd64".to_string()));
+}
+
+#[test]
+fn test_nothing_pending() {
+    let root = scratch_root("clean");
+    write(&root, "proc/sys/kernel/osrelease", "6.1.0-18-amd64\n", 1_700_000_000);
+    write(&root, "lib/modules/6.1.0-18-amd64/modules.dep", "", 1_700_000_000);
+    write(&root, "boot/vmlinuz-6.1.0-17-amd64", "", 1_690_000_000);
+
+    let status = RebootStatus::read(&root);
+    fs::remove_dir_all(&root).unwrap();
+    assert!(!status.is_pending());
+    assert_eq!(status.describe(|_| String::new()), "not pending");
+}
+
+#[test]
+fn test_boot_images_named_after_packages() {
+    let root = scratch_root("arch");
+    write(&root, "proc/sys/kernel/osrelease", "6.8.1-arch1-1\n", 1_700_000_000);
+    write(&root, "lib/modules/6.8.1-arch1-1/modules.dep", "", 1_700_000_000);
+    write(&root, "boot/vmlinuz-linux", "", 1_700_000_000);
+    write(&root, "boot/vmlinuz-linux-lts", "", 1_700_000_000);
+
+    let status = RebootStatus::read(&root);
+    fs::remove_dir_all(&root).unwrap();
+    assert!(!status.is_pending(), "{:?}", status.reasons);
+}
+
+#[test]
+fn test_kernel_upgrade_and_marker() {
+    let root = scratch_root("upgrade");
+    write(&root, "proc/sys/kernel/osrelease", "6.1.0-18-amd64\n", 1_700_000_000);
+    write(&root, "lib/modules/6.1.0-18-amd64/modules.dep", "", 1_700_000_000);
+    write(&root, "lib/modules/6.1.0-20-amd64/modules.dep", "", 1_709_280_000);
+    // Leftover of a removed kernel: no modules.dep
+    fs::create_dir_all(root.join("lib/modules/6.9.0-1-amd64/updates")).unwrap();
+    write(&root, "var/run/reboot-required", "*** System restart required ***\n", 1_709_290_000);
+    write(&root, "var/run/reboot-required.pkgs", "linux-image-6.1.0-20-amd64\nlibc6\nlibc6\n", 1_709_290_000);
+
+    let status = RebootStatus::read(&root);
+    fs::remove_dir_all(&root).unwrap();
+    assert_eq!(status.reasons, [
+        RebootReason::Marker {
+            path: "/var/run/reboot-required".to_string(),
+            packages: vec!["linux-image-6.1.0-20-amd64".to_string(), "libc6".to_string()],
+        },
+        RebootReason::KernelUpgrade { running: "6.1.0-18-amd64".to_string(), installed: "6.1.0-20-amd64".to_string() },
+    ]);
+    assert_eq!(status.since, DateTime::from_timestamp(1_709_280_000, 0), "the earliest evidence wins");
+    assert_eq!(status.describe(|since| since.format("%Y-%m-%d %H:%M").to_string()),
+        "pending since 2024-03-01 08:00 (requested by linux-image-6.1.0-20-amd64, libc6; kernel 6.1.0-20-amd64 installed)");
+}
+
+#[test]
+fn test_running_kernel_removed() {
+    let root = scratch_root("removed");
+    write(&root, "proc/version", "Linux version 6.7.4-arch1-1 (linux@archlinux) #1 SMP PREEMPT_DYNAMIC\n", 1_700_000_000);
+    write(&root, "usr/lib/modules/6.7.3-arch1-1/modules.dep", "", 1_700_000_000);
+
+    let status = RebootStatus::read(&root);
+    assert_eq!(status.reasons, [RebootReason::KernelRemoved { running: "6.7.4-arch1-1".to_string() }]);
+    assert_eq!(status.since, None);
+
+    // Containers run the host's kernel; only markers count there
+    write(&root, ".dockerenv", "", 1_700_000_000);
+    assert!(!RebootStatus::read(&root).is_pending());
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,470 @@
+//! Snapshot tests pinning the exact output of every format
+//!
+//! Metrics come from a fixed source and the clock is pinned, so the output
+//! only depends on the inputs below.
+
+use chrono::{DateTime, Utc};
+use runtime::{Runtime, OutputFormat, clock::Clock, render::{ColorChoice, CountKind, Field}};
+use runtime::system_metrics::{MetricsSource, SchedulerStats, SystemMetrics, TaskCounts};
+use runtime::time_format::{TimeSettings, TimeStyle, TimeZoneChoice};
+use runtime::host_info::{HostInfo, OsRelease, Virtualization};
+use runtime::wtmp::{BootEnd, BootRecord};
+
+/// 2024-03-01 12:34:56 UTC
+const NOW: i64 = 1_709_296_496;
+
+fn now() -> DateTime<Utc> {
+    DateTime::from_timestamp(NOW, 0).unwrap()
+}
+
+fn fixture(uptime: f64, load: (f64, f64, f64), users: usize) -> SystemMetrics {
+    SystemMetrics::default()
+        .with_uptime_seconds(uptime)
+        .with_idle_time(uptime / 2.0)
+        .with_load_averages(load)
+        .with_user_count(users)
+        .with_boot_time(NOW as u64 - uptime as u64)
+}
+
+/// Timestamps in UTC, so the output does not depend on the local zone
+fn utc() -> TimeSettings {
+    TimeSettings { zone: TimeZoneChoice::Utc, ..TimeSettings::default() }
+}
+
+fn render(metrics: &SystemMetrics, format: OutputFormat, show_container: bool) -> String {
+    Runtime::builder()
+        .format(format)
+        .color(ColorChoice::Never)
+        .clock(Clock::Fixed(now()))
+        .time(utc())
+        .metrics_source(MetricsSource::Fixed(Box::new(metrics.clone())))
+        .show_container(show_container)
+        .build()
+        .to_string()
+}
+
+fn check(metrics: SystemMetrics, standard: &str, pretty: &str, raw: &str, since: &str, interactive: &str) {
+    assert_eq!(render(&metrics, OutputFormat::Standard, false), standard);
+    assert_eq!(render(&metrics, OutputFormat::Pretty, false), pretty);
+    assert_eq!(render(&metrics, OutputFormat::Raw, false), raw);
+    assert_eq!(render(&metrics, OutputFormat::Since, false), since);
+    assert_eq!(render(&metrics, OutputFormat::Interactive, false), interactive);
+}
+
+#[test]
+fn snapshot_zero() {
+    check(
+        fixture(0.0, (0.0, 0.0, 0.0), 0),
+        " 12:34:56 up 0 min, 0 users, load average: 0.00, 0.00, 0.00",
+        "up less than a minute",
+        "1709296496 0.000000 0 0.00 0.00 0.00 - - - - - - -",
+        "2024-03-01 12:34:56",
+        r#"
++=======================================================+
+|             *  SYSTEM UPTIME DASHBOARD  *             |
++=======================================================+
+| Current Time    : 12:34:56 UTC                        |
+| System Uptime   : 0s                                  |
+| Boot Time       : 2024-03-01 12:34:56                 |
+| Active Users    : 0 users                             |
+| Load Average    : 0.00, 0.00, 0.00  - steady          |
+| System Mode     : [NATIVE]                            |
++=======================================================+
+"#,
+    );
+}
+
+#[test]
+fn snapshot_under_a_minute() {
+    check(
+        fixture(59.5, (0.05, 0.1, 0.15), 1),
+        " 12:34:56 up 0 min, 1 user, load average: 0.05, 0.10, 0.15",
+        "up less than a minute",
+        "1709296437 59.500000 29.75 0.05 0.10 0.15 - - - - - - -",
+        "2024-03-01 12:33:57",
+        r#"
++=======================================================+
+|             *  SYSTEM UPTIME DASHBOARD  *             |
++=======================================================+
+| Current Time    : 12:34:56 UTC                        |
+| System Uptime   : 59s                                 |
+| Boot Time       : 2024-03-01 12:33:57                 |
+| Active Users    : 1 user                              |
+| Load Average    : 0.05, 0.10, 0.15  - steady          |
+| System Mode     : [NATIVE]                            |
++=======================================================+
+"#,
+    );
+}
+
+#[test]
+fn snapshot_one_hour() {
+    check(
+        fixture(3600.0, (1.0, 0.5, 0.25), 2),
+        " 12:34:56 up  1:00, 2 users, load average: 1.00, 0.50, 0.25",
+        "up 1 hour",
+        "1709292896 3600.000000 1800 1.00 0.50 0.25 - - - - - - -",
+        "2024-03-01 11:34:56",
+        r#"
++=======================================================+
+|             *  SYSTEM UPTIME DASHBOARD  *             |
++=======================================================+
+| Current Time    : 12:34:56 UTC                        |
+| System Uptime   : 1h                                  |
+| Boot Time       : 2024-03-01 11:34:56                 |
+| Active Users    : 2 users                             |
+| Load Average    : 1.00, 0.50, 0.25  ^ rising          |
+| System Mode     : [NATIVE]                            |
++=======================================================+
+"#,
+    );
+}
+
+#[test]
+fn snapshot_hours() {
+    check(
+        fixture(18423.75, (2.5, 3.0, 3.99), 1),
+        " 12:34:56 up  5:07, 1 user, load average: 2.50, 3.00, 3.99",
+        "up 5 hours, 7 minutes",
+        "1709278073 18423.750000 9211.875 2.50 3.00 3.99 - - - - - - -",
+        "2024-03-01 07:27:53",
+        r#"
++=======================================================+
+|             *  SYSTEM UPTIME DASHBOARD  *             |
++=======================================================+
+| Current Time    : 12:34:56 UTC                        |
+| System Uptime   : 5h 7m 3s                            |
+| Boot Time       : 2024-03-01 07:27:53                 |
+| Active Users    : 1 user                              |
+| Load Average    : 2.50, 3.00, 3.99  v falling         |
+| System Mode     : [NATIVE]                            |
++=======================================================+
+"#,
+    );
+}
+
+#[test]
+fn snapshot_whole_days() {
+    check(
+        fixture(172800.0, (4.0, 8.25, 16.5), 12),
+        " 12:34:56 up 2 days, 0 min, 12 users, load average: 4.00, 8.25, 16.50",
+        "up 2 days",
+        "1709123696 172800.000000 86400 4.00 8.25 16.50 - - - - - - -",
+        "2024-02-28 12:34:56",
+        r#"
++=======================================================+
+|             *  SYSTEM UPTIME DASHBOARD  *             |
++=======================================================+
+| Current Time    : 12:34:56 UTC                        |
+| System Uptime   : 2d                                  |
+| Boot Time       : 2024-02-28 12:34:56                 |
+| Active Users    : 12 users                            |
+| Load Average    : 4.00, 8.25, 16.50  v falling        |
+| System Mode     : [NATIVE]                            |
++=======================================================+
+"#,
+    );
+}
+
+#[test]
+fn snapshot_weeks() {
+    check(
+        fixture(3466921.0, (0.99, 1.99, 3.99), 3),
+        " 12:34:56 up 40 days,  3:02, 3 users, load average: 0.99, 1.99, 3.99",
+        "up 5 weeks, 5 days, 3 hours, 2 minutes",
+        "1705829575 3466921.000000 1733460.5 0.99 1.99 3.99 - - - - - - -",
+        "2024-01-21 09:32:55",
+        r#"
++=======================================================+
+|             *  SYSTEM UPTIME DASHBOARD  *             |
++=======================================================+
+| Current Time    : 12:34:56 UTC                        |
+| System Uptime   : 40d 3h 2m 1s                        |
+| Boot Time       : 2024-01-21 09:32:55                 |
+| Active Users    : 3 users                             |
+| Load Average    : 0.99, 1.99, 3.99  v falling         |
+| System Mode     : [NATIVE]                            |
++=======================================================+
+"#,
+    );
+}
+
+#[test]
+fn snapshot_container() {
+    let metrics = fixture(18423.75, (2.5, 3.0, 3.99), 1);
+
+    assert_eq!(render(&metrics, OutputFormat::Standard, true),
+        " 12:34:56 up  5:07 (container), 1 user, load average: 2.50, 3.00, 3.99");
+    assert!(render(&metrics, OutputFormat::Interactive, true)
+        .contains("| System Mode     : [CONTAINER]                         |\n"));
+}
+
+#[test]
+fn snapshot_session_and_tty_counts() {
+    let metrics = fixture(18423.75, (2.5, 3.0, 3.99), 1)
+        .with_session_count(8)
+        .with_tty_count(1);
+    let render_counts = |format: OutputFormat, counts: Vec<CountKind>| {
+        Runtime::builder()
+            .format(format)
+            .color(ColorChoice::Never)
+            .clock(Clock::Fixed(now()))
+            .time(utc())
+            .counts(counts)
+            .metrics_source(MetricsSource::Fixed(Box::new(metrics.clone())))
+            .build()
+            .to_string()
+    };
+
+    assert_eq!(render_counts(OutputFormat::Standard, vec![CountKind::Users, CountKind::Sessions, CountKind::Ttys]),
+        " 12:34:56 up  5:07, 1 user, 8 sessions, 1 tty, load average: 2.50, 3.00, 3.99");
+    assert_eq!(render_counts(OutputFormat::Standard, vec![CountKind::Sessions]),
+        " 12:34:56 up  5:07, 8 sessions, load average: 2.50, 3.00, 3.99");
+    assert!(render_counts(OutputFormat::Interactive, vec![CountKind::Users, CountKind::Ttys])
+        .contains("| Active Users    : 1 user, 1 tty                       |\n"));
+}
+
+#[test]
+fn snapshot_last_shutdown() {
+    let boot = |end| BootRecord {
+        started: DateTime::from_timestamp(NOW - 90_000, 0).unwrap(),
+        end,
+        kernel: "6.1.0".to_string(),
+        boot_id: None,
+        last_seen: None,
+    };
+    let clean = boot(BootEnd::Clean(DateTime::from_timestamp(NOW - 20_000, 0).unwrap()));
+    let metrics = fixture(18423.75, (2.5, 3.0, 3.99), 1);
+
+    let clean_output = render(&metrics.clone().with_previous_boot(clean), OutputFormat::Interactive, false);
+    assert!(clean_output.contains("| Boot Time       : 2024-03-01 07:27:53                 |\n\
+                                   | Last Shutdown   : clean, 2024-03-01 07:01:36          |\n"));
+    let crash_output = render(&metrics.clone().with_previous_boot(boot(BootEnd::Crash)), OutputFormat::Interactive, false);
+    assert!(crash_output.contains("| Last Shutdown   : crash (no shutdown record)          |\n"));
+    assert!(!render(&metrics, OutputFormat::Interactive, false).contains("Last Shutdown"));
+}
+
+#[test]
+fn snapshot_host_header_and_json() {
+    let host = HostInfo {
+        hostname: Some("web-01".to_string()),
+        kernel_release: Some("6.1.0-18-amd64".to_string()),
+        os: OsRelease::parse("NAME=\"Debian GNU/Linux\"\nVERSION=\"12 (bookworm)\"\nID=debian\n"),
+        architecture: Some("x86_64".to_string()),
+        virtualization: Some(Virtualization::Vm(Some("kvm".to_string()))),
+    };
+    let metrics = fixture(18423.75, (2.5, 3.0, 3.99), 1).with_host(host);
+
+    assert!(render(&metrics, OutputFormat::Interactive, false).contains(
+        "+=======================================================+\n\
+         | Hostname           : web-01                           |\n\
+         | Operating System   : Debian GNU/Linux 12 (bookworm)   |\n\
+         | Kernel             : 6.1.0-18-amd64 (x86_64)          |\n\
+         | Virtualization     : kvm (vm)                         |\n\
+         +=======================================================+\n\
+         | Current Time       : 12:34:56 UTC                     |\n"));
+
+    assert_eq!(render(&metrics, OutputFormat::Json, false), r#"{
+  "time": "2024-03-01T12:34:56Z",
+  "uptime_seconds": 18423.75,
+  "idle_seconds": 9211.875,
+  "awake_seconds": null,
+  "suspended_seconds": null,
+  "boot_time": "2024-03-01T07:27:53Z",
+  "users": 1,
+  "sessions": null,
+  "ttys": null,
+  "load": [
+    2.5,
+    3.0,
+    3.99
+  ],
+  "load_trend": {
+    "trend": "falling",
+    "short_term": -0.5,
+    "long_term": -1.49
+  },
+  "tasks": null,
+  "scheduler": null,
+  "container": false,
+  "host": {
+    "hostname": "web-01",
+    "kernel": "6.1.0-18-amd64",
+    "architecture": "x86_64",
+    "os": {
+      "id": "debian",
+      "name": "Debian GNU/Linux",
+      "version": "12 (bookworm)",
+      "pretty_name": null
+    },
+    "virtualization": {
+      "type": "vm",
+      "name": "kvm"
+    }
+  },
+  "reboot": null,
+  "cgroup": null,
+  "processes": null
+}"#);
+    assert!(render(&SystemMetrics::default(), OutputFormat::Json, false).contains("\"host\": null"));
+
+    let host_fields = |format| {
+        Runtime::builder()
+            .format(format)
+            .clock(Clock::Fixed(now()))
+            .metrics_source(MetricsSource::Fixed(Box::new(metrics.clone())))
+            .fields(vec![Field::Hostname, Field::Kernel, Field::Os, Field::OsVersion, Field::Arch, Field::Virtualization])
+            .build()
+            .to_string()
+    };
+    assert_eq!(host_fields(OutputFormat::Csv),
+        "hostname,kernel,os,os_version,arch,virtualization\nweb-01,6.1.0-18-amd64,debian,12 (bookworm),x86_64,vm");
+    assert_eq!(host_fields(OutputFormat::Tsv),
+        "hostname\tkernel\tos\tos_version\tarch\tvirtualization\nweb-01\t6.1.0-18-amd64\tdebian\t12 (bookworm)\tx86_64\tvm");
+    assert_eq!(host_fields(OutputFormat::KeyValue), "\
+RUNTIME_HOSTNAME=web-01
+RUNTIME_KERNEL=6.1.0-18-amd64
+RUNTIME_OS=debian
+RUNTIME_OS_VERSION='12 (bookworm)'
+RUNTIME_ARCH=x86_64
+RUNTIME_VIRTUALIZATION=vm");
+    assert!(render(&metrics, OutputFormat::Prometheus, false).ends_with("\
+# HELP runtime_host_info Host identification; the value is always 1.
+# TYPE runtime_host_info gauge
+runtime_host_info{hostname=\"web-01\",kernel=\"6.1.0-18-amd64\",os=\"debian\",version=\"12 (bookworm)\",arch=\"x86_64\",virtualization=\"vm\"} 1
+"));
+
+    let container = HostInfo { virtualization: Some(Virtualization::Container(Some("docker".to_string()))), ..HostInfo::default() };
+    assert!(render(&metrics.clone().with_host(container), OutputFormat::Interactive, false)
+        .contains("| System Mode        : [CONTAINER]                      |\n"));
+}
+
+#[test]
+fn snapshot_tasks_and_scheduler() {
+    let scheduler = SchedulerStats { procs_running: 3, procs_blocked: 1, context_switches: 48_213_977, forks: 91_204 };
+    let metrics = fixture(18423.75, (2.5, 3.0, 3.99), 1)
+        .with_tasks(TaskCounts { runnable: 2, total: 812, last_pid: 91_187 })
+        .with_scheduler(scheduler);
+
+    assert!(render(&metrics, OutputFormat::Interactive, false).contains(
+        "| Load Average    : 2.50, 3.00, 3.99  v falling                       |\n\
+         | Tasks           : 2 runnable of 812, last PID 91187                 |\n\
+         | Scheduler       : 3 running, 1 blocked, 48.2M switches, 91.2K forks |\n"));
+    assert_eq!(render(&metrics, OutputFormat::Raw, false),
+        "1709278073 18423.750000 9211.875 2.50 3.00 3.99 2 812 91187 3 1 48213977 91204");
+    assert!(render(&metrics, OutputFormat::Json, false).contains(r#"
+  "tasks": {
+    "runnable": 2,
+    "total": 812,
+    "last_pid": 91187
+  },
+  "scheduler": {
+    "procs_running": 3,
+    "procs_blocked": 1,
+    "context_switches": 48213977,
+    "forks": 91204
+  },
+"#));
+    assert_eq!(render(&metrics, OutputFormat::Prometheus, false), "\
+# HELP runtime_uptime_seconds Time since boot, including time suspended.
+# TYPE runtime_uptime_seconds gauge
+runtime_uptime_seconds 18423.75
+# HELP runtime_idle_seconds Time all CPUs spent idle since boot.
+# TYPE runtime_idle_seconds gauge
+runtime_idle_seconds 9211.875
+# HELP runtime_boot_time_seconds Boot time as a UNIX timestamp.
+# TYPE runtime_boot_time_seconds gauge
+runtime_boot_time_seconds 1709278073
+# HELP runtime_users Logged-in users.
+# TYPE runtime_users gauge
+runtime_users 1
+# HELP runtime_load1 1-minute load average.
+# TYPE runtime_load1 gauge
+runtime_load1 2.5
+# HELP runtime_load5 5-minute load average.
+# TYPE runtime_load5 gauge
+runtime_load5 3
+# HELP runtime_load15 15-minute load average.
+# TYPE runtime_load15 gauge
+runtime_load15 3.99
+# HELP runtime_tasks_runnable Runnable tasks from /proc/loadavg.
+# TYPE runtime_tasks_runnable gauge
+runtime_tasks_runnable 2
+# HELP runtime_tasks Tasks (processes and threads) from /proc/loadavg.
+# TYPE runtime_tasks gauge
+runtime_tasks 812
+# HELP runtime_last_pid PID most recently handed out.
+# TYPE runtime_last_pid gauge
+runtime_last_pid 91187
+# HELP runtime_procs_running Processes running or waiting for a CPU.
+# TYPE runtime_procs_running gauge
+runtime_procs_running 3
+# HELP runtime_procs_blocked Processes blocked waiting for I/O.
+# TYPE runtime_procs_blocked gauge
+runtime_procs_blocked 1
+# HELP runtime_context_switches_total Context switches since boot.
+# TYPE runtime_context_switches_total counter
+runtime_context_switches_total 48213977
+# HELP runtime_forks_total Processes and threads created since boot.
+# TYPE runtime_forks_total counter
+runtime_forks_total 91204
+");
+    assert_eq!(render(&SystemMetrics::default(), OutputFormat::Prometheus, false), "", "unknown metrics are left out");
+}
+
+#[test]
+fn snapshot_unknown() {
+    check(
+        SystemMetrics::default(),
+        " 12:34:56 up unknown, unknown users, load average: unknown",
+        "up unknown",
+        "- - - - - - - - - - - - -",
+        "unknown",
+        r#"
++=======================================================+
+|             *  SYSTEM UPTIME DASHBOARD  *             |
++=======================================================+
+| Current Time    : 12:34:56 UTC                        |
+| System Uptime   : unknown                             |
+| Boot Time       : unknown                             |
+| Active Users    : unknown                             |
+| Load Average    : unknown                             |
+| System Mode     : [NATIVE]                            |
++=======================================================+
+"#,
+    );
+}
+
+#[test]
+fn snapshot_time_settings() {
+    let metrics = fixture(18423.75, (2.5, 3.0, 3.99), 1);
+    let render_with = |zone: &str, style: TimeStyle, format: OutputFormat| {
+        Runtime::builder()
+            .format(format)
+            .color(ColorChoice::Never)
+            .clock(Clock::Fixed(now()))
+            .time(TimeSettings { zone: zone.parse().unwrap(), style })
+            .metrics_source(MetricsSource::Fixed(Box::new(metrics.clone())))
+            .build()
+            .to_string()
+    };
+
+    assert_eq!(render_with("utc", TimeStyle::Default, OutputFormat::Since), "2024-03-01 07:27:53");
+    assert_eq!(render_with("Asia/Tokyo", TimeStyle::Default, OutputFormat::Since), "2024-03-01 16:27:53");
+    assert_eq!(render_with("America/New_York", TimeStyle::Rfc3339, OutputFormat::Since),
+        "2024-03-01T02:27:53-05:00");
+    assert_eq!(render_with("utc", TimeStyle::Rfc3339, OutputFormat::Since), "2024-03-01T07:27:53Z");
+    assert_eq!(render_with("utc", TimeStyle::custom("%s").unwrap(), OutputFormat::Since), "1709278073");
+    assert_eq!(render_with("Europe/Berlin", TimeStyle::Default, OutputFormat::Standard),
+        " 13:34:56 up  5:07, 1 user, load average: 2.50, 3.00, 3.99");
+
+    let table = render_with("Europe/Berlin", TimeStyle::Default, OutputFormat::Interactive);
+    assert!(table.contains("| Current Time    : 13:34:56 CET "));
+    assert!(table.contains("| Boot Time       : 2024-03-01 08:27:53 "));
+}
+
+#[test]
+fn time_settings_reject_bad_input() {
+    assert!("Mars/Olympus_Mons".parse::<TimeZoneChoice>().is_err());
+    assert!(TimeStyle::custom("%Q").is_err());
+}
@@ -0,0 +1,91 @@
+use runtime::glyphs::{Glyphs, Icon};
+use runtime::table::{strip_ansi, visible_width, Table};
+
+fn plain(border: &str) -> String {
+    border.to_string()
+}
+
+#[test]
+fn test_visible_width_ignores_escape_codes() {
+    assert_eq!(visible_width("\u{1b}[1;96m12\u{1b}[0md"), 3);
+    assert_eq!(visible_width("\u{1b}]8;;https://example.com\u{7}link\u{1b}]8;;\u{1b}\\"), 4);
+    assert_eq!(strip_ansi("\u{1b}[2munknown\u{1b}[0m"), "unknown");
+}
+
+#[test]
+fn test_visible_width_counts_wide_characters() {
+    assert_eq!(visible_width("東京"), 4);
+    assert_eq!(visible_width("é"), 1);
+}
+
+#[test]
+fn test_rows_are_aligned() {
+    let table = Table::new("TITLE")
+        .row("Short", "\u{1b}[1;92m1\u{1b}[0m")
+        .row("Timezone", "2024-03-01 12:34:56 America/Argentina/ComodRivadavia")
+        .row("City", "東京");
+    let rendered = table.render(None, plain);
+
+    let widths: Vec<usize> = rendered.lines().skip(1).map(visible_width).collect();
+    assert!(widths.iter().all(|width| *width == widths[0]), "{}", rendered);
+    assert!(widths[0] > 57, "table should grow past the minimum width");
+    assert!(rendered.contains("| Short      : \u{1b}[1;92m1\u{1b}[0m "));
+}
+
+#[test]
+fn test_minimum_width_and_narrow_fit() {
+    let table = Table::new("T").row("Label", "value");
+
+    let wide = table.render(Some(200), plain);
+    assert_eq!(wide.lines().nth(1).map(visible_width), Some(57));
+
+    let narrow = table.render(Some(30), plain);
+    assert_eq!(narrow, "\n+============================+\n|             T              |\n+============================+\n| Label   : value            |\n+============================+\n");
+}
+
+#[test]
+fn test_compact_layout_for_tiny_terminals() {
+    let table = Table::new("T").row("Label", "a rather long value");
+    assert_eq!(table.render(Some(20), plain), "\n== T ==\nLabel\n  a rather long value\n");
+}
+
+#[test]
+fn test_box_drawing_borders() {
+    let table = Table::new("T").row("Label", "value").glyphs(Glyphs::Unicode);
+    assert_eq!(table.render(Some(30), plain), "\n┌────────────────────────────┐\n│             T              │\n├────────────────────────────┤\n│ Label   : value            │\n└────────────────────────────┘\n");
+    assert_eq!(table.render(Some(10), plain), "\n── T ──\nLabel\n  value\n");
+}
+
+#[test]
+fn test_icons_keep_rows_aligned() {
+    for glyphs in [Glyphs::Unicode, Glyphs::Nerd] {
+        let table = Table::new("T")
+            .glyphs(glyphs)
+            .row(glyphs.label(Icon::Time, "Current Time"), "12:34:56")
+            .row(glyphs.label(Icon::Boot, "Boot Time"), "2024-03-01");
+        let rendered = table.render(None, plain);
+
+        let widths: Vec<usize> = rendered.lines().skip(1).map(visible_width).collect();
+        assert!(widths.iter().all(|width| *width == 57), "{}", rendered);
+    }
+    assert_eq!(Glyphs::Ascii.label(Icon::Time, "Current Time"), "Current Time");
+}
+
+#[test]
+fn test_separator_between_sections() {
+    let table = Table::new("T")
+        .separator()
+        .row("Host", "web-01")
+        .separator()
+        .separator()
+        .row("Uptime", "1h")
+        .separator();
+
+    let lines: Vec<String> = table.render(None, plain).lines().map(str::to_string).collect();
+    let rules = lines.iter().filter(|line| line.starts_with('+')).count();
+    assert_eq!(rules, 4, "leading, repeated and trailing separators draw nothing");
+    assert!(lines[5].starts_with("+==="));
+    assert!(lines[6].starts_with("| Uptime"));
+
+    assert_eq!(table.render(Some(10), plain), "\n== T ==\nHost\n  web-01\n\nUptime\n  1h\n");
+}
@@ -1,5 +1,11 @@
 use std::time::Duration;
-use runtime::{Runtime, system_metrics::SystemMetrics};
+use chrono::DateTime;
+use runtime::{Runtime, OutputFormat, clock::Clock, render::{self, ColorChoice, Field, RenderOptions, UptimeClock}};
+use runtime::cgroup::{CgroupStats, Pressure};
+use runtime::host_info::HostInfo;
+use runtime::process::ProcessInfo;
+use runtime::clock::{KernelClock, KernelClockReading};
+use runtime::system_metrics::{CollectOptions, MetricSet, MetricsSource, Progress, SchedulerStats, SystemMetrics, TaskCounts};
 
 #[test]
 fn test_refresh_ne_1sec() {
@@ -23,14 +29,14 @@ fn test_system_refresh_ne_1sec() {
 #[test]
 fn test_system_metrics_creation() {
     let metrics = SystemMetrics::new().expect("Should be able to create SystemMetrics");
-    assert!(metrics.uptime_seconds() > 0.0, "Uptime should be positive");
-    assert!(metrics.user_count() > 0, "Should have at least one user");
+    assert!(metrics.uptime_seconds().expect("Uptime should be measured") > 0.0, "Uptime should be positive");
+    assert!(metrics.user_count().expect("User count should be measured") > 0, "Should have at least one user");
 }
 
 #[test]
 fn test_load_averages() {
     let metrics = SystemMetrics::new().expect("Should be able to create SystemMetrics");
-    let (load1, load5, load15) = metrics.load_averages();
+    let (load1, load5, load15) = metrics.load_averages().expect("Load averages should be measured");
     assert!(load1 >= 0.0, "Load average should be non-negative");
     assert!(load5 >= 0.0, "Load average should be non-negative");
     assert!(load15 >= 0.0, "Load average should be non-negative");
@@ -39,7 +45,7 @@ fn test_load_averages() {
 #[test]
 fn test_boot_time() {
     let metrics = SystemMetrics::new().expect("Should be able to create SystemMetrics");
-    let boot_time = metrics.boot_time();
+    let boot_time = metrics.boot_time().expect("Boot time should be measured");
     assert!(boot_time > 0, "Boot time should be positive");
 
     // Boot time should be reasonable (not in the future, not too old)
@@ -51,3 +57,268 @@ fn test_boot_time() {
     assert!(boot_time <= now, "Boot time should not be in the future");
     assert!(boot_time > now - (365 * 24 * 3600), "Boot time should not be more than a year ago");
 }
+
+#[test]
+fn test_default_metrics_are_unknown() {
+    let metrics = SystemMetrics::default();
+    assert_eq!(metrics.uptime_seconds(), None);
+    assert_eq!(metrics.idle_time(), None);
+    assert_eq!(metrics.load_averages(), None);
+    assert_eq!(metrics.user_count(), None);
+    assert_eq!(metrics.boot_time(), None);
+}
+
+#[test]
+fn test_builder_fixed_source() {
+    let metrics = SystemMetrics::default()
+        .with_uptime_seconds(7530.25)
+        .with_idle_time(100.5)
+        .with_load_averages((0.5, 1.25, 2.0))
+        .with_user_count(3)
+        .with_boot_time(1_700_000_000);
+    let mut rt = Runtime::builder()
+        .format(OutputFormat::Raw)
+        .color(ColorChoice::Never)
+        .metrics_source(MetricsSource::Fixed(Box::new(metrics.clone())))
+        .build();
+    rt.refresh();
+
+    assert_eq!(rt.metrics(), &metrics);
+    assert_eq!(rt.to_string(), "1700000000 7530.250000 100.5 0.50 1.25 2.00 - - - - - - -");
+    assert_eq!(rt.render(&OutputFormat::Pretty), "up 2 hours, 5 minutes");
+}
+
+#[test]
+fn test_render_unknown_metrics() {
+    let metrics = SystemMetrics::default();
+    let options = RenderOptions { color: ColorChoice::Never, ..RenderOptions::default() };
+
+    assert_eq!(render::raw(&metrics, &options), "- - - - - - - - - - - - -");
+    assert_eq!(render::pretty(&metrics, &options), "up unknown");
+    assert_eq!(render::since(&metrics, &options), "unknown");
+    assert!(render::standard(&metrics, &options)
+        .ends_with(" up unknown, unknown users, load average: unknown"));
+
+    let table = render::interactive(&metrics, &options);
+    assert!(table.contains("Boot Time       : unknown"));
+    assert!(!table.contains("1970"));
+}
+
+#[test]
+fn test_boot_time_follows_clock() {
+    let now = DateTime::from_timestamp(2_000_000_000, 0).unwrap();
+    let mut metrics = SystemMetrics::default();
+    metrics.refresh_with_clock(&Clock::Fixed(now)).expect("Failed to refresh SystemMetrics");
+
+    let uptime = metrics.uptime_seconds().expect("Uptime should be measured") as u64;
+    assert_eq!(metrics.boot_time(), Some(2_000_000_000 - uptime));
+}
+
+#[test]
+fn test_offset_clock() {
+    let clock = Clock::Offset(chrono::Duration::hours(-1));
+    let drift = chrono::Utc::now() - clock.now();
+    assert!((drift - chrono::Duration::hours(1)).num_seconds().abs() <= 1);
+}
+
+#[test]
+fn test_refresh_reports_progress() {
+    let mut steps = Vec::new();
+    let mut metrics = SystemMetrics::default();
+    let _ = metrics.refresh_with_progress(&Clock::System, &mut |progress| steps.push(progress));
+
+    let names: Vec<&str> = steps.iter().map(|progress| progress.step).collect();
+    assert_eq!(names[..names.len() - 1], Progress::steps().collect::<Vec<_>>());
+    assert!(steps.last().unwrap().is_done());
+    assert!(!steps[0].is_done());
+}
+
+#[test]
+fn test_metric_set_operations() {
+    let set = MetricSet::UPTIME | MetricSet::LOAD;
+    assert!(set.contains(MetricSet::LOAD));
+    assert!(!set.contains(MetricSet::USERS));
+    assert_eq!(set | set.complement(), MetricSet::ALL);
+    assert!(MetricSet::EMPTY.is_empty());
+    assert_eq!(MetricSet::BOOT_TIME.with_dependencies(), MetricSet::BOOT_TIME | MetricSet::UPTIME);
+}
+
+#[test]
+fn test_since_skips_the_user_scan() {
+    let runtime = Runtime::builder().format(OutputFormat::Since).build();
+    assert!(!runtime.metric_set().contains(MetricSet::USERS));
+    assert_eq!(runtime.metrics().user_count(), None);
+    assert!(runtime.metrics().boot_time().is_some());
+
+    let mut steps = Vec::new();
+    let mut metrics = SystemMetrics::default().with_user_count(3);
+    let _ = metrics.refresh_selected(&MetricSet::BOOT_TIME.into(), &Clock::System, &mut |progress| steps.push(progress.step));
+    assert_eq!(steps, ["uptime", "boot time", "done"]);
+    assert_eq!(metrics.user_count(), None, "unselected metrics are cleared");
+}
+
+#[test]
+fn test_awake_and_suspended_time() {
+    let reading = KernelClockReading { boottime: 90_000.0, monotonic: 18_000.5 };
+    assert_eq!(reading.suspended(), 71_999.5);
+    assert_eq!(KernelClockReading { boottime: 10.0, monotonic: 10.2 }.suspended(), 0.0);
+    // Reading the clocks back to back leaves a gap of a few hundred nanoseconds
+    assert_eq!(KernelClockReading { boottime: 18_000.500_000_4, monotonic: 18_000.5 }.suspended(), 0.0);
+    assert_eq!(KernelClockReading { boottime: 18_000.5, monotonic: 18_000.499_999_7 }.suspended(), 0.0);
+
+    let options = CollectOptions {
+        kernel_clock: KernelClock::Fixed(reading),
+        ..MetricSet::UPTIME.into()
+    };
+    let mut metrics = SystemMetrics::default();
+    let _ = metrics.refresh_selected(&options, &Clock::System, &mut |_| {});
+    assert_eq!(metrics.awake_seconds(), Some(18_000.5));
+    assert_eq!(metrics.suspended_seconds(), Some(71_999.5));
+
+    let jitter = KernelClockReading { boottime: 18_000.500_000_3, monotonic: 18_000.5 };
+    let _ = metrics.refresh_selected(&CollectOptions { kernel_clock: KernelClock::Fixed(jitter), ..options }, &Clock::System, &mut |_| {});
+    assert_eq!(metrics.suspended_seconds(), Some(0.0), "a machine that never slept reports no suspend");
+
+    let live = KernelClock::System.read().expect("Kernel clocks should be readable");
+    assert!(live.boottime >= live.monotonic);
+}
+
+#[test]
+fn test_uptime_clock_choice() {
+    let metrics = SystemMetrics::default()
+        .with_uptime_seconds(90_000.0)
+        .with_awake_seconds(18_000.0)
+        .with_suspended_seconds(72_000.0);
+    let options = |uptime_clock| RenderOptions { uptime_clock, ..RenderOptions::default() };
+
+    assert_eq!(render::pretty(&metrics, &options(UptimeClock::Boot)), "up 1 day, 1 hour");
+    assert_eq!(render::pretty(&metrics, &options(UptimeClock::Awake)), "up 5 hours");
+    assert_eq!(render::raw(&metrics, &options(UptimeClock::Awake)), "- 90000.000000 - - - - - - - - - - -");
+
+    let table = render::interactive(&metrics, &RenderOptions { color: ColorChoice::Never, ..options(UptimeClock::Awake) });
+    assert!(table.contains("| Awake Time     : 5h   "));
+    assert!(table.contains("| Suspended      : 20h   "));
+}
+
+#[test]
+fn test_cgroup_subject_formats() {
+    let cgroup = CgroupStats {
+        name: "nginx.service".to_string(),
+        main_pid: Some(812),
+        started: DateTime::from_timestamp(1_709_280_500, 0),
+        uptime_seconds: Some(187_200.0),
+        cpu_seconds: Some(3723.0),
+        memory_bytes: Some(126_353_408),
+        cpu_pressure: Some(Pressure { avg10: 0.12, avg60: 0.08, avg300: 0.05 }),
+        ..CgroupStats::default()
+    };
+    let metrics = SystemMetrics::default().with_uptime_seconds(900_000.0).with_cgroup(cgroup);
+    let options = RenderOptions {
+        time: runtime::time_format::TimeSettings { zone: runtime::time_format::TimeZoneChoice::Utc, ..Default::default() },
+        clock: Clock::Fixed(DateTime::from_timestamp(1_709_467_700, 0).unwrap()),
+        ..RenderOptions::default()
+    };
+
+    assert_eq!(render::pretty(&metrics, &options), "nginx.service up 2 days, 4 hours");
+    assert_eq!(render::standard(&metrics, &options),
+        " 12:08:20 nginx.service up 2 days,  4:00, pid 812, cpu 1h 2m 3s, memory 120.5 MiB, cpu pressure: 0.12, 0.08, 0.05");
+    assert_eq!(render::raw(&metrics, &options), "1709280500 187200.000000 3723.000000 126353408 0.12 0.08 0.05");
+    assert_eq!(render::since(&metrics, &options), "2024-03-01 08:08:20");
+
+    let runtime = Runtime::builder()
+        .format(OutputFormat::Standard)
+        .cgroup(Some(runtime::cgroup::CgroupTarget::Unit("nginx".to_string())))
+        .metrics_source(MetricsSource::Fixed(Box::default()))
+        .build();
+    assert_eq!(runtime.metric_set(), MetricSet::CGROUP, "one-line formats skip the host metrics");
+}
+
+#[test]
+fn test_process_subject_formats() {
+    let process = |pid, uptime_seconds: f64| ProcessInfo {
+        pid,
+        name: "nginx".to_string(),
+        started: DateTime::from_timestamp(1_709_467_700 - uptime_seconds as i64, 0),
+        uptime_seconds: Some(uptime_seconds),
+        cpu_seconds: Some(65.0),
+    };
+    let options = RenderOptions {
+        time: runtime::time_format::TimeSettings { zone: runtime::time_format::TimeZoneChoice::Utc, ..Default::default() },
+        clock: Clock::Fixed(DateTime::from_timestamp(1_709_467_700, 0).unwrap()),
+        ..RenderOptions::default()
+    };
+
+    let one = SystemMetrics::default().with_processes(vec![process(812, 187_200.0)]);
+    assert_eq!(render::pretty(&one, &options), "nginx (812) up 2 days, 4 hours");
+    assert_eq!(render::standard(&one, &options), " 12:08:20 nginx (812) up 2 days,  4:00, cpu 1m 5s");
+    assert_eq!(render::since(&one, &options), "2024-03-01 08:08:20");
+
+    let two = SystemMetrics::default().with_processes(vec![process(812, 187_200.0), process(813, 90.0)]);
+    let table = "PID  COMMAND  STARTED              UPTIME  CPU\n\
+                 812  nginx    2024-03-01 08:08:20  2d 4h   1m 5s\n\
+                 813  nginx    2024-03-03 12:06:50  1m 30s  1m 5s";
+    assert_eq!(render::standard(&two, &options), table);
+    assert_eq!(render::pretty(&two, &options), table);
+    assert_eq!(render::raw(&two, &options), "812 1709280500 187200.000000 65.000000\n813 1709467610 90.000000 65.000000");
+
+    // Columns line up on display width, not bytes
+    let mut wide = process(814, 90.0);
+    wide.name = "café日本".to_string();
+    let mixed = SystemMetrics::default().with_processes(vec![process(812, 187_200.0), wide]);
+    assert_eq!(render::standard(&mixed, &options),
+        "PID  COMMAND   STARTED              UPTIME  CPU\n\
+         812  nginx     2024-03-01 08:08:20  2d 4h   1m 5s\n\
+         814  café日本  2024-03-03 12:06:50  1m 30s  1m 5s");
+}
+
+#[test]
+fn test_parse_tasks_and_scheduler() {
+    assert_eq!(TaskCounts::parse("0.20 0.18 0.12 1/80 11206\n"),
+        Some(TaskCounts { runnable: 1, total: 80, last_pid: 11206 }));
+    assert_eq!(TaskCounts::parse("0.20 0.18 0.12"), None, "older kernels or truncated files");
+    assert_eq!(TaskCounts::parse("0.20 0.18 0.12 1-80 11206"), None);
+
+    let stat = "cpu  10132153 290696 3084719 46828483 16683 0 25195 0 0 0\n\
+                cpu0 1393280 32966 572056 13343292 6130 0 17875 0 0 0\n\
+                intr 1462898 0 0\n\
+                ctxt 115315\n\
+                btime 769041601\n\
+                processes 86031\n\
+                procs_running 6\n\
+                procs_blocked 2\n";
+    assert_eq!(SchedulerStats::parse(stat),
+        Some(SchedulerStats { procs_running: 6, procs_blocked: 2, context_switches: 115315, forks: 86031 }));
+    assert_eq!(SchedulerStats::parse("cpu  1 2 3 4\nctxt 115315\n"), None);
+}
+
+#[test]
+fn test_field_formats() {
+    let metrics = SystemMetrics::default()
+        .with_uptime_seconds(7530.256)
+        .with_idle_time(100.5)
+        .with_load_averages((0.5, 1.25, 2.0))
+        .with_boot_time(1_700_000_000)
+        .with_tasks(TaskCounts { runnable: 2, total: 80, last_pid: 11206 });
+    let options = RenderOptions { color: ColorChoice::Never, ..RenderOptions::default() };
+
+    assert_eq!(render::csv(&metrics, &options),
+        "boot_time,uptime_seconds,idle_seconds,load1,load5,load15,tasks_runnable,tasks_total,last_pid,\
+         procs_running,procs_blocked,context_switches,forks,hostname,kernel,os,os_version,arch,virtualization\n\
+         1700000000,7530.26,100.50,0.50,1.25,2.00,2,80,11206,,,,,,,,,,");
+
+    let selected = RenderOptions {
+        fields: vec![Field::UptimeSeconds, Field::Load1, Field::Forks],
+        ..options.clone()
+    };
+    assert_eq!(render::tsv(&metrics, &selected), "uptime_seconds\tload1\tforks\n7530.26\t0.50\t");
+    assert_eq!(render::csv(&metrics, &RenderOptions { omit_header: true, ..selected.clone() }), "7530.26,0.50,");
+    assert_eq!(render::key_value(&metrics, &selected), "RUNTIME_UPTIME_SECONDS=7530.26\nRUNTIME_LOAD1=0.50\nRUNTIME_FORKS=");
+
+    let quoted = SystemMetrics::default().with_host(HostInfo { hostname: Some("it's, \"odd\"".to_string()), ..HostInfo::default() });
+    let hostname = RenderOptions { fields: vec![Field::Hostname], omit_header: true, ..options.clone() };
+    assert_eq!(render::csv(&quoted, &hostname), r#""it's, ""odd""""#);
+    assert_eq!(render::key_value(&quoted, &hostname), r#"RUNTIME_HOSTNAME='it'\''s, "odd"'"#);
+
+    assert_eq!("last_pid".parse::<Field>(), Ok(Field::LastPid));
+    assert!("uptime".parse::<Field>().unwrap_err().starts_with("unknown field 'uptime'"));
+}
@@ -0,0 +1,75 @@
+use runtime::render::{self, ColorChoice, RenderOptions};
+use runtime::system_metrics::SystemMetrics;
+use runtime::theme::{Color, ColorDepth, Painter, Role, Style, Theme, BUILTIN_THEMES};
+
+#[test]
+fn test_colors_parse() {
+    assert_eq!("#ff8000".parse::<Color>(), Ok(Color::Rgb(255, 128, 0)));
+    assert_eq!("208".parse::<Color>(), Ok(Color::Indexed(208)));
+    assert_eq!("bright-cyan".parse::<Color>(), Ok(Color::Ansi(14)));
+    assert_eq!("bright_cyan".parse::<Color>(), Ok(Color::Ansi(14)));
+    assert!("#ff80".parse::<Color>().is_err());
+    assert!("teal".parse::<Color>().is_err());
+
+    let style: Style = "#ff8000 bold".parse().unwrap();
+    assert_eq!(style, Style { fg: Some(Color::Rgb(255, 128, 0)), bold: true, dim: false });
+}
+
+#[test]
+fn test_colors_downsample_to_terminal_depth() {
+    let theme = Theme::parse("orange", "value = \"#ff8700\"").unwrap();
+    let paint = |depth| Painter::new(&theme, depth, true).paint(Role::Value, "x");
+
+    assert_eq!(paint(ColorDepth::TrueColor), "\u{1b}[38;2;255;135;0mx\u{1b}[0m");
+    assert_eq!(paint(ColorDepth::Ansi256), "\u{1b}[38;5;208mx\u{1b}[0m");
+    assert_eq!(paint(ColorDepth::Ansi16), "\u{1b}[33mx\u{1b}[0m");
+}
+
+#[test]
+fn test_dark_theme_keeps_classic_colors() {
+    let theme = Theme::dark();
+    let painter = Painter::new(&theme, ColorDepth::Ansi16, true);
+
+    assert_eq!(painter.paint(Role::Border, "+"), "\u{1b}[1;94m+\u{1b}[0m");
+    assert_eq!(painter.paint(Role::Muted, "users"), "\u{1b}[2musers\u{1b}[0m");
+    assert_eq!(Painter::new(&theme, ColorDepth::Ansi16, false).paint(Role::Border, "+"), "+");
+}
+
+#[test]
+fn test_builtin_themes_load_by_name() {
+    for name in BUILTIN_THEMES {
+        assert_eq!(Theme::load(name).unwrap().name(), name);
+    }
+    assert!(Theme::load("no-such-theme").is_err());
+}
+
+#[test]
+fn test_theme_file_inherits_and_overrides() {
+    let theme = Theme::parse("mine", r##"
+        # comments and blank lines are ignored
+        inherits = "light"
+        border = "#268bd2 bold"   # trailing comment
+        muted = "dim"
+    "##).unwrap();
+
+    assert_eq!(theme.style(Role::Border), Style { fg: Some(Color::Rgb(38, 139, 210)), bold: true, dim: false });
+    assert_eq!(theme.style(Role::Muted), Style { fg: None, bold: false, dim: true });
+    assert_eq!(theme.style(Role::LoadHigh), Theme::light().style(Role::LoadHigh));
+
+    assert!(Theme::parse("bad", "borders = \"red\"").unwrap_err().contains("unknown role"));
+    assert!(Theme::parse("bad", "border = \"mauve\"").unwrap_err().contains("line 1"));
+}
+
+#[test]
+fn test_interactive_uses_the_selected_theme() {
+    let metrics = SystemMetrics::default().with_load_averages((0.5, 1.5, 5.0));
+    let options = RenderOptions {
+        color: ColorChoice::Always,
+        theme: Theme::high_contrast(),
+        color_depth: Some(ColorDepth::Ansi16),
+        ..RenderOptions::default()
+    };
+
+    assert_eq!(render::load_fancy(&metrics, &options),
+        "\u{1b}[1;92m0.50\u{1b}[0m, \u{1b}[1;93m1.50\u{1b}[0m, \u{1b}[1;95m5.00\u{1b}[0m");
+}
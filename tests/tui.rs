@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use chrono::DateTime;
+use runtime::clock::Clock;
+use runtime::render::RenderOptions;
+use runtime::system_metrics::{CpuTimes, MemoryInfo, Session, SystemMetrics};
+use runtime::time_format::{TimeSettings, TimeZoneChoice};
+use runtime::tui::{self, gauge, sparkline, History, Panel, Sample, ViewState};
+
+fn options() -> RenderOptions {
+    RenderOptions {
+        clock: Clock::Fixed(DateTime::from_timestamp(1_709_296_496, 0).unwrap()),
+        time: TimeSettings { zone: TimeZoneChoice::Utc, ..TimeSettings::default() },
+        ..RenderOptions::default()
+    }
+}
+
+fn metrics() -> SystemMetrics {
+    SystemMetrics::default()
+        .with_uptime_seconds(274_325.0)
+        .with_load_averages((2.0, 1.0, 0.5))
+        .with_sessions(vec![
+            Session { uid: 0, tty: "tty1".to_string() },
+            Session { uid: 1000, tty: "pts/0".to_string() },
+            Session { uid: 1000, tty: "pts/3".to_string() },
+        ])
+}
+
+fn history() -> History {
+    let mut history = History::new(8);
+    for step in 0..10 {
+        let load = step as f64 / 4.0;
+        history.push_sample(Sample {
+            uptime: Some(step as f64 * 2.0),
+            load: Some((load, load / 2.0, 0.5)),
+            cpu_percent: Some(35.0),
+            memory_percent: Some(50.0),
+        });
+    }
+    history
+}
+
+#[test]
+fn test_overview_screen() {
+    let view = ViewState { load_threshold: 4.0, ..ViewState::new(Duration::from_secs(2)) };
+    let screen = tui::render(&history(), &metrics(), &view, &options(), 40, 18);
+
+    assert_eq!(screen.lines(), [
+        "runtime top - 12:34:56  up 3d 4h 12m 5s ",
+        "[running] refresh every 2s              ",
+        "[1 Overview]  2 Load   3 Sessions       ",
+        "                                        ",
+        "Load  1m   2.25  ▂▃▄▄▅▆▇█               ",
+        "Load  5m   1.12  ▁▁▂▂▃▃▄▄               ",
+        "Load 15m   0.50  ▂▂▂▂▂▂▂▂               ",
+        "Trend ^ rising, +7.50/min, 4.00 in 14s  ",
+        "                                        ",
+        "CPU  [########................]  35.0%  ",
+        "Mem  [############............]  50.0%  ",
+        "                                        ",
+        "Sessions: 3                             ",
+        "  UID      TTY                          ",
+        "  0        tty1                         ",
+        "  1000     pts/0                        ",
+        "  1000     pts/3                        ",
+        "q quit  space pause  +/- refresh rate  t",
+    ]);
+}
+
+#[test]
+fn test_sessions_panel_truncates() {
+    let mut view = ViewState::new(Duration::from_secs(2));
+    view.handle_key(b'3');
+    let screen = tui::render(&history(), &metrics(), &view, &options(), 30, 8);
+
+    assert_eq!(screen.lines().len(), 8);
+    assert_eq!(screen.lines()[4].trim_end(), "Sessions: 3");
+    assert_eq!(screen.lines()[6].trim_end(), "  ... 3 more");
+}
+
+#[test]
+fn test_keys_change_view() {
+    let mut view = ViewState::new(Duration::from_secs(2));
+
+    assert!(view.handle_key(b'+'));
+    assert_eq!(view.interval, Duration::from_secs(1));
+    view.handle_key(b'-');
+    view.handle_key(b'-');
+    assert_eq!(view.interval, Duration::from_secs(4));
+    for _ in 0..10 {
+        view.handle_key(b'+');
+    }
+    assert_eq!(view.interval, tui::MIN_INTERVAL);
+
+    view.handle_key(b' ');
+    assert!(view.paused);
+    view.handle_key(b'\t');
+    assert_eq!(view.panel, Panel::Load);
+
+    assert!(!view.handle_key(b'q'));
+}
+
+#[test]
+fn test_history_derives_cpu_usage() {
+    let mut history = History::new(4);
+    let memory = MemoryInfo { total_kib: 1000, available_kib: 250 };
+    history.push(&SystemMetrics::default().with_cpu_times(CpuTimes { busy: 100, total: 1000 }).with_memory(memory));
+    history.push(&SystemMetrics::default().with_cpu_times(CpuTimes { busy: 150, total: 1200 }).with_memory(memory));
+
+    let samples: Vec<&Sample> = history.samples().collect();
+    assert_eq!(samples[0].cpu_percent, None);
+    assert_eq!(samples[1].cpu_percent, Some(25.0));
+    assert_eq!(samples[1].memory_percent, Some(75.0));
+}
+
+#[test]
+fn test_chart_helpers() {
+    assert_eq!(sparkline(&[0.0, 0.5, 1.0], 10, 1.0), " ▄█");
+    assert_eq!(sparkline(&[0.0, 4.0, 8.0], 2, 8.0), "▄█");
+    assert_eq!(gauge(Some(50.0), 4), "[##..]  50.0%");
+    assert_eq!(gauge(None, 4), "[....]    n/a");
+}
@@ -0,0 +1,78 @@
+use runtime::config::Config;
+use runtime::users::{parse_login_defs, parse_passwd, PasswdEntry, UserFilter};
+
+const PASSWD: &str = "\
+root:x:0:0:root:/root:/bin/bash
+backup:x:34:34:backup:/var/backups:/usr/sbin/nologin
+svc-deploy:x:1001:1001::/home/svc-deploy:/bin/sh
+broken line
+alice:x:10000:10000:Alice:/home/alice:/bin/zsh
+";
+
+#[test]
+fn test_parse_passwd_and_login_defs() {
+    let passwd = parse_passwd(PASSWD);
+    assert_eq!(passwd.len(), 4);
+    assert_eq!(passwd[3], PasswdEntry { name: "alice".to_string(), uid: 10000 });
+
+    let login_defs = "# comment\nUID_MIN\t\t\t  500\nUID_MAX   29999\nGID_MIN 500\n";
+    assert_eq!(parse_login_defs(login_defs), (Some(500), Some(29999)));
+    assert_eq!(parse_login_defs(""), (None, None));
+}
+
+#[test]
+fn test_default_filter_counts_regular_users_and_root() {
+    let filter = UserFilter::default();
+    assert!(filter.matches(0));
+    assert!(filter.matches(1000));
+    assert!(!filter.matches(999));
+    assert!(!filter.matches(65534), "nobody is outside the range");
+}
+
+#[test]
+fn test_filter_uses_login_defs_range() {
+    let filter = UserFilter::from_sources("UID_MIN 500\nUID_MAX 999\n", &[], &Config::default()).unwrap();
+    assert!(filter.matches(500));
+    assert!(!filter.matches(1000));
+}
+
+#[test]
+fn test_config_overrides_range_and_lists() {
+    let config = Config::parse(r#"
+        [users]
+        uid_min = 10000
+        include = ["root", "backup"]
+        exclude = ["svc-deploy", 10001]
+    "#).unwrap();
+    let filter = UserFilter::from_sources("UID_MIN 500\n", &parse_passwd(PASSWD), &config).unwrap();
+
+    assert!(filter.matches(10000));
+    assert!(!filter.matches(10001), "excluded by UID");
+    assert!(!filter.matches(1001), "below the configured range");
+    assert!(filter.matches(34), "included by name");
+    assert!(filter.matches(0));
+}
+
+#[test]
+fn test_include_adds_to_root_and_exclude_removes_it() {
+    let passwd = parse_passwd(PASSWD);
+    let config = Config::parse("[users]\ninclude = [\"backup\"]\n").unwrap();
+    let filter = UserFilter::from_sources("", &passwd, &config).unwrap();
+    assert!(filter.matches(34));
+    assert!(filter.matches(0), "root stays counted");
+
+    let config = Config::parse("[users]\ninclude = [\"backup\"]\nexclude = [\"root\"]\n").unwrap();
+    let filter = UserFilter::from_sources("", &passwd, &config).unwrap();
+    assert!(filter.matches(34));
+    assert!(!filter.matches(0), "excluded explicitly");
+}
+
+#[test]
+fn test_unknown_names_are_errors() {
+    let config = Config::parse("[users]\nexclude = [\"mallory\"]\n").unwrap();
+    let error = UserFilter::from_sources("", &parse_passwd(PASSWD), &config).unwrap_err();
+    assert!(error.contains("mallory"), "{}", error);
+
+    let config = Config::parse("[users]\nuid_min = -5\n").unwrap();
+    assert!(UserFilter::from_sources("", &[], &config).is_err());
+}
@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use runtime::clock::Clock;
+use runtime::render::{self, ColorChoice, RenderOptions};
+use runtime::system_metrics::{CollectOptions, MetricSet, SystemMetrics};
+use runtime::time_format::{TimeSettings, TimeZoneChoice};
+use runtime::wtmp::{self, BootEnd, RecordKind, RECORD_SIZE};
+
+/// A `struct utmp` with the fields wtmp boot history uses
+fn record(kind: i16, user: &str, host: &str, time: i64) -> Vec<u8> {
+    let mut bytes = vec![0u8; RECORD_SIZE];
+    bytes[0..2].copy_from_slice(&kind.to_ne_bytes());
+    bytes[4..8].copy_from_slice(&1i32.to_ne_bytes());
+    bytes[8..9].copy_from_slice(b"~");
+    bytes[44..44 + user.len()].copy_from_slice(user.as_bytes());
+    bytes[76..76 + host.len()].copy_from_slice(host.as_bytes());
+    bytes[340..344].copy_from_slice(&(time as i32).to_ne_bytes());
+    bytes
+}
+
+fn at(seconds: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(seconds, 0).unwrap()
+}
+
+/// Clean shutdown, then a crash, then the running boot, with a login in between
+fn history() -> Vec<u8> {
+    [
+        record(2, "reboot", "6.1.0", 1_700_000_000),
+        record(7, "alice", "10.0.0.5", 1_700_000_100),
+        record(1, "shutdown", "6.1.0", 1_700_003_600),
+        record(2, "reboot", "6.1.0", 1_700_010_000),
+        record(2, "reboot", "6.2.0", 1_700_020_000),
+    ]
+    .concat()
+}
+
+#[test]
+fn test_parse_records() {
+    let mut bytes = history();
+    bytes.extend_from_slice(&[0u8; 100]);
+    let records = wtmp::parse_records(&bytes);
+
+    assert_eq!(records.len(), 5, "a truncated trailing record is ignored");
+    assert_eq!(records[1].kind, RecordKind::UserProcess);
+    assert_eq!(records[1].user, "alice");
+    assert_eq!(records[1].host, "10.0.0.5");
+    assert!(records[0].is_boot());
+    assert!(records[2].is_shutdown());
+    assert_eq!(records[2].time, at(1_700_003_600));
+}
+
+#[test]
+fn test_boots_detect_crashes() {
+    let boots = wtmp::boots(&wtmp::parse_records(&history()));
+
+    let ends: Vec<BootEnd> = boots.iter().map(|boot| boot.end).collect();
+    assert_eq!(ends, [BootEnd::Clean(at(1_700_003_600)), BootEnd::Crash, BootEnd::Running]);
+    assert_eq!(boots[2].kernel, "6.2.0");
+    assert_eq!(wtmp::previous_boot(&boots).map(|boot| boot.end), Some(BootEnd::Crash));
+    assert_eq!(wtmp::previous_boot(&boots[..1]), None);
+}
+
+#[test]
+fn test_cross_reference_journal() {
+    let text = std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/wtmp/list-boots.txt")).unwrap();
+    let journal = wtmp::parse_list_boots(&text).unwrap();
+    assert_eq!(journal.len(), 3);
+    assert_eq!(journal[0].index, -2);
+
+    let mut boots = wtmp::boots(&wtmp::parse_records(&history()));
+    wtmp::cross_reference(&mut boots, &journal);
+    assert_eq!(boots[1].boot_id.as_deref(), Some("9a8b7c6d5e4f40312a1b2c3d4e5f6a7b"));
+    assert_eq!(boots[1].ended(), Some(at(1_700_016_067)), "a crashed boot ends at its last journal entry");
+
+    assert!(wtmp::parse_list_boots("-1 abc Wed 2023-11-15 01:00:05 CET Wed 2023-11-15 02:41:07 CET").is_err());
+}
+
+#[test]
+fn test_last_boots_listing() {
+    let mut boots = wtmp::boots(&wtmp::parse_records(&history()));
+    let options = RenderOptions {
+        color: ColorChoice::Never,
+        time: TimeSettings { zone: TimeZoneChoice::Utc, ..TimeSettings::default() },
+        ..RenderOptions::default()
+    };
+
+    assert_eq!(render::last_boots(&boots, at(1_700_023_600), &options),
+        "STARTED              ENDED                DURATION  SHUTDOWN\n\
+         2023-11-15 03:46:40  running              1h        -\n\
+         2023-11-15 01:00:00  unknown              unknown   crash\n\
+         2023-11-14 22:13:20  2023-11-14 23:13:20  1h        clean");
+
+    boots[0].boot_id = Some("0f2b4c1e".to_string());
+    assert!(render::last_boots(&boots, at(1_700_023_600), &options).starts_with(
+        "STARTED              ENDED                DURATION  SHUTDOWN  BOOT ID\n"));
+}
+
+#[test]
+fn test_collect_previous_boot() {
+    let path = std::env::temp_dir().join(format!("runtime-wtmp-{}", std::process::id()));
+    std::fs::write(&path, history()).unwrap();
+    let options = CollectOptions {
+        metrics: MetricSet::BOOT_HISTORY,
+        wtmp_path: Some(path.clone()),
+        ..CollectOptions::default()
+    };
+
+    let mut metrics = SystemMetrics::default();
+    metrics.refresh_selected(&options, &Clock::System, &mut |_| {}).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(metrics.previous_boot().map(|boot| boot.end), Some(BootEnd::Crash));
+
+    let missing = CollectOptions { wtmp_path: Some(path), ..options };
+    let mut metrics = SystemMetrics::default();
+    metrics.refresh_selected(&missing, &Clock::System, &mut |_| {}).unwrap();
+    assert_eq!(metrics.previous_boot(), None, "a missing wtmp is not an error");
+}